@@ -1,14 +1,17 @@
-use std::{borrow::BorrowMut, collections::HashMap, fs};
+use std::{collections::HashMap, fs};
 
-use log::{debug, info};
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Runtime, Window};
 
-use crate::tauri_api_ext::ConfPathExt;
+use crate::{guides::SummaryQuestStatus, storage::ReadError, tauri_api_ext::ConfPathExt};
 
 // Constants
 
 const DEFAULT_LEVEL: u32 = 200;
+pub const DEFAULT_GUIDE_VERSIONS_RETENTION: u32 = 5;
+pub const DEFAULT_IMAGE_CACHE_MAX_SIZE_MB: u32 = 512;
+pub const DEFAULT_TRAVEL_COMMAND_TEMPLATE: &str = "/travel {x},{y}";
 
 const fn default_level() -> u32 {
     DEFAULT_LEVEL
@@ -18,6 +21,18 @@ const fn default_auto_open_guides() -> bool {
     true
 }
 
+const fn default_guide_versions_retention() -> u32 {
+    DEFAULT_GUIDE_VERSIONS_RETENTION
+}
+
+const fn default_image_cache_max_size_mb() -> u32 {
+    DEFAULT_IMAGE_CACHE_MAX_SIZE_MB
+}
+
+fn default_travel_command_template() -> String {
+    DEFAULT_TRAVEL_COMMAND_TEMPLATE.to_string()
+}
+
 fn default_reset_conf_shortcut() -> String {
     "Alt+Shift+P".to_string()
 }
@@ -122,6 +137,10 @@ pub struct Profile {
     pub progresses: Vec<Progress>,
     #[serde(default)]
     pub server_id: Option<u32>,
+    /// Names of the quests the profile completed, recorded when passing the step ending them.
+    /// Only edited through `setQuestCompleted`, the value sent with `set` is ignored.
+    #[serde(default)]
+    pub completed_quests: Vec<String>,
 }
 
 #[derive(Debug)]
@@ -178,6 +197,18 @@ pub struct Conf {
     pub auto_open_guides: bool,
     #[serde(default)]
     pub shortcuts: Shortcuts,
+    /// Number of previous versions kept for each guide, 0 disables the history.
+    #[serde(default = "default_guide_versions_retention")]
+    pub guide_versions_retention: u32,
+    /// Whether the next step shortcut jumps over the steps of quests already completed.
+    #[serde(default)]
+    pub skip_completed_steps: bool,
+    /// Size of the guide images cache on disk, the least recently used images are evicted first.
+    #[serde(default = "default_image_cache_max_size_mb")]
+    pub image_cache_max_size_mb: u32,
+    /// Travel command of a step, `{x}`, `{y}` and `{map}` are replaced by its position.
+    #[serde(default = "default_travel_command_template")]
+    pub travel_command_template: String,
 }
 
 // Functions
@@ -229,14 +260,11 @@ fn toggle_conf_step_checkbox(step: &mut ConfStep, checkbox_index: u32) {
 pub fn get_conf<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Conf, Error> {
     let conf_path = app_handle.path().app_conf_file();
 
-    let file = fs::read_to_string(conf_path);
-
-    match file {
-        Err(err) => match err.kind() {
-            std::io::ErrorKind::NotFound => Ok(Conf::default()),
-            _ => Err(Error::UnhandledIo(err.to_string())),
-        },
-        Ok(file) => Ok(crate::json::from_str::<Conf>(file.as_str()).map_err(Error::Malformed)?),
+    match crate::storage::read_json::<Conf>(&conf_path) {
+        Ok(Some(conf)) => Ok(conf),
+        Ok(None) => Ok(Conf::default()),
+        Err(ReadError::Io(err)) => Err(Error::UnhandledIo(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::Malformed(err)),
     }
 }
 
@@ -247,7 +275,7 @@ pub fn save_conf<R: Runtime>(conf: &mut Conf, app: &AppHandle<R>) -> Result<(),
 
     let json = crate::json::serialize_pretty(conf).map_err(Error::SerializeConf)?;
 
-    fs::write(conf_path, json).map_err(|err| Error::SaveConf(err.to_string()))
+    crate::storage::write(&conf_path, json).map_err(|err| Error::SaveConf(err.to_string()))
 }
 
 pub fn backup_conf<R: Runtime>(app: &AppHandle<R>) -> Result<(), Error> {
@@ -270,6 +298,88 @@ fn get_conf_profile_in_use_mut(conf: &mut Conf) -> Result<&mut Profile, Error> {
         .ok_or(Error::GetProfileInUse)
 }
 
+/// Whether a profile completed the quest `name`, names are compared case insensitively.
+pub fn is_quest_completed(profile: &Profile, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+
+    profile
+        .completed_quests
+        .iter()
+        .any(|quest| quest.to_lowercase() == name)
+}
+
+fn set_quest_completed(profile: &mut Profile, name: &str, completed: bool) {
+    let name = name.trim();
+
+    if completed {
+        if !name.is_empty() && !is_quest_completed(profile, name) {
+            profile.completed_quests.push(name.to_string());
+        }
+    } else {
+        let name = name.to_lowercase();
+
+        profile
+            .completed_quests
+            .retain(|quest| quest.to_lowercase() != name);
+    }
+}
+
+/// Keep the completed quests stored for each profile and record the quests ended in the steps
+/// passed since the stored progresses.
+fn record_completed_quests<R: Runtime>(conf: &mut Conf, previous: &Conf, app: &AppHandle<R>) {
+    for profile in conf.profiles.iter_mut() {
+        let Some(previous_profile) = previous.profiles.iter().find(|p| p.id == profile.id) else {
+            continue;
+        };
+
+        profile.completed_quests = previous_profile.completed_quests.clone();
+
+        let passed = profile
+            .progresses
+            .iter()
+            .filter_map(|progress| {
+                let from_step = previous_profile
+                    .progresses
+                    .iter()
+                    .find(|p| p.id == progress.id)
+                    .map_or(0, |p| p.current_step);
+
+                (progress.current_step > from_step).then_some((
+                    progress.id,
+                    from_step,
+                    progress.current_step,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        for (guide_id, from_step, to_step) in passed {
+            let guide = match crate::guides::get_installed_guide(app, guide_id) {
+                Ok(guide) => guide,
+                Err(err) => {
+                    debug!("[Conf] cannot read guide {guide_id} to record its quests: {err}");
+                    continue;
+                }
+            };
+
+            for quest in crate::guides::extract_guide_quests(&guide) {
+                // a step is passed when leaving it, step numbers start at 1
+                let ended = quest.statuses.iter().any(|status| {
+                    matches!(status, SummaryQuestStatus::Completed(number) if *number > from_step && *number <= to_step)
+                });
+
+                if ended {
+                    debug!(
+                        "[Conf] quest {} completed by profile {}",
+                        quest.name, profile.id
+                    );
+
+                    set_quest_completed(profile, &quest.name, true);
+                }
+            }
+        }
+    }
+}
+
 fn normalize_conf(conf: &mut Conf) {
     conf.opacity = conf.opacity.clamp(0.0, 0.98);
 }
@@ -336,6 +446,10 @@ impl Default for Conf {
             opacity: 0.98,
             auto_open_guides: true,
             shortcuts: Shortcuts::default(),
+            guide_versions_retention: DEFAULT_GUIDE_VERSIONS_RETENTION,
+            skip_completed_steps: false,
+            image_cache_max_size_mb: DEFAULT_IMAGE_CACHE_MAX_SIZE_MB,
+            travel_command_template: default_travel_command_template(),
         }
     }
 }
@@ -348,6 +462,7 @@ impl Default for Profile {
             level: 200,
             progresses: vec![],
             server_id: None,
+            completed_quests: vec![],
         }
     }
 }
@@ -393,6 +508,12 @@ pub trait ConfApi {
         checkbox_index: u32,
     ) -> Result<u32, Error>;
     async fn reset<R: Runtime>(app_handle: AppHandle<R>, window: Window<R>) -> Result<(), Error>;
+    #[taurpc(alias = "setQuestCompleted")]
+    async fn set_quest_completed<R: Runtime>(
+        app_handle: AppHandle<R>,
+        quest_name: String,
+        completed: bool,
+    ) -> Result<Vec<String>, Error>;
 }
 
 #[derive(Clone)]
@@ -405,7 +526,23 @@ impl ConfApi for ConfApiImpl {
     }
 
     async fn set<R: Runtime>(self, conf: Conf, app: AppHandle<R>) -> Result<(), Error> {
-        save_conf(conf.clone().borrow_mut(), &app)
+        let mut conf = conf;
+        // a broken conf must still be overwritable, there is just nothing to compare with then
+        let previous = get_conf(&app)
+            .inspect_err(|err| warn!("[Conf] cannot read the previous conf: {}", err))
+            .ok();
+
+        if let Some(previous) = &previous {
+            record_completed_quests(&mut conf, previous, &app);
+        }
+
+        save_conf(&mut conf, &app)?;
+
+        if let Some(previous) = &previous {
+            crate::travel::copy_travel_on_step_change(&app, &conf, previous);
+        }
+
+        Ok(())
     }
 
     async fn toggle_guide_checkbox<R: Runtime>(
@@ -460,4 +597,24 @@ impl ConfApi for ConfApiImpl {
 
         Ok(())
     }
+
+    async fn set_quest_completed<R: Runtime>(
+        self,
+        app: AppHandle<R>,
+        quest_name: String,
+        completed: bool,
+    ) -> Result<Vec<String>, Error> {
+        debug!("[Conf] set_quest_completed: {quest_name}, completed: {completed}");
+
+        let conf = &mut get_conf(&app)?;
+        let profile = get_conf_profile_in_use_mut(conf)?;
+
+        set_quest_completed(profile, &quest_name, completed);
+
+        let completed_quests = profile.completed_quests.clone();
+
+        save_conf(conf, &app)?;
+
+        Ok(completed_quests)
+    }
 }
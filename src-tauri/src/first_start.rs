@@ -22,8 +22,8 @@ impl<R: Runtime> FirstStartExt for AppHandle<R> {
         let version = self.package_info().version.to_string();
 
         if !exists {
-            std::fs::write(
-                first_time_start,
+            crate::storage::write(
+                &first_time_start,
                 format!(r###"{{"version": "{version}"}}"###, version = version),
             )
             .map_err(Error::Write)?;
@@ -0,0 +1,690 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    conf::Progress,
+    guide_library::GuidesLibrary,
+    guides::{
+        get_guides_from_handle, get_installed_guide, is_local_guide, next_local_guide_id,
+        resolve_folder, used_guide_ids, write_guide_and_remap, write_guides, Error, GuideWithSteps,
+        Guides,
+    },
+    pinned_guides::MAX_PINNED_PER_PROFILE,
+    step_notes::GuideStepNotes,
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Constants
+
+pub const BUNDLE_EXTENSION: &str = "ganymede";
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+// Enums
+
+/// What to do when a guide of the bundle is already installed.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleConflictStrategy {
+    /// Keep the installed guide.
+    Skip,
+    /// Replace the installed guide with the one of the bundle.
+    Replace,
+    /// Keep whichever guide was updated last.
+    KeepNewest,
+}
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct BundleExport {
+    pub path: String,
+    pub guides_count: u32,
+}
+
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct BundleImport {
+    pub imported: Vec<u32>,
+    pub replaced: Vec<u32>,
+    pub skipped: Vec<u32>,
+}
+
+/// Content of a `.ganymede` file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuideBundle {
+    version: u32,
+    exported_at: String,
+    /// Every folder of the bundle, relative to its root, so empty folders are kept too.
+    folders: Vec<String>,
+    guides: Vec<BundledGuide>,
+    #[serde(default)]
+    profile: Option<BundledProfile>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledGuide {
+    /// Folder of the guide relative to the root of the bundle, empty for the root.
+    folder: String,
+    /// Guide without its `folder`, which is an absolute path on the exporting machine.
+    guide: GuideWithSteps,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundledProfile {
+    progresses: Vec<Progress>,
+    step_notes: HashMap<u32, GuideStepNotes>,
+    pinned_guides: Vec<u32>,
+}
+
+/// What importing the guides of a bundle does to the library.
+#[derive(Default)]
+struct ImportPlan {
+    /// Guides to write, with the installed guide each one replaces.
+    guides: Vec<(GuideWithSteps, Option<GuideWithSteps>)>,
+    /// New ids of the local guides of the bundle, by their id in the bundle.
+    new_ids: HashMap<u32, u32>,
+    result: BundleImport,
+}
+
+// Implementations
+
+impl BundledGuide {
+    fn new(folder: String, mut guide: GuideWithSteps) -> Self {
+        guide.folder = None;
+
+        BundledGuide { folder, guide }
+    }
+}
+
+impl BundledProfile {
+    /// Move the progress, notes and pins of the guides given a new id on import.
+    fn remap_guide_ids(&mut self, new_ids: &HashMap<u32, u32>) {
+        let new_id = |id: u32| new_ids.get(&id).copied().unwrap_or(id);
+
+        for progress in &mut self.progresses {
+            progress.id = new_id(progress.id);
+        }
+
+        self.step_notes = std::mem::take(&mut self.step_notes)
+            .into_iter()
+            .map(|(guide_id, notes)| (new_id(guide_id), notes))
+            .collect();
+
+        for guide_id in &mut self.pinned_guides {
+            *guide_id = new_id(*guide_id);
+        }
+    }
+}
+
+// Public Functions
+
+/// Export guides and folders, with their subfolders, into a single `.ganymede` file. Exported
+/// folders keep their name at the root of the bundle, guides exported by id are put at its root.
+pub fn export_bundle<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    path: String,
+    guide_ids: Vec<u32>,
+    folders: Vec<String>,
+    include_profile: bool,
+) -> Result<BundleExport, Error> {
+    info!(
+        "[Bundle] exporting guides {:?} and folders {:?} to {}",
+        guide_ids, folders, path
+    );
+
+    let guides_dir = app_handle.path().app_guides_dir();
+    let mut bundle = GuideBundle {
+        version: BUNDLE_FORMAT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        folders: vec![],
+        guides: vec![],
+        profile: None,
+    };
+    let mut exported_ids = HashSet::new();
+
+    for folder in folders {
+        let folder_path = resolve_folder(&guides_dir, &folder)?;
+
+        if !folder_path.is_dir() {
+            return Err(Error::InvalidFolder(folder));
+        }
+
+        // the exported folder is kept in the bundle, unless the whole library is exported
+        let parent = match folder_path.parent() {
+            Some(parent) if !folder.is_empty() => parent.to_path_buf(),
+            _ => folder_path.clone(),
+        };
+
+        for dir in list_dirs(&folder_path)? {
+            bundle.folders.push(relative_folder(&parent, &dir));
+        }
+
+        for guide in get_guides_from_handle(app_handle, folder)?.guides {
+            if exported_ids.insert(guide.id) {
+                let folder = guide
+                    .folder
+                    .as_deref()
+                    .map(|folder| relative_folder(&parent, folder))
+                    .unwrap_or_default();
+
+                bundle.guides.push(BundledGuide::new(folder, guide));
+            }
+        }
+    }
+
+    for guide_id in guide_ids {
+        if exported_ids.insert(guide_id) {
+            bundle.guides.push(BundledGuide::new(
+                String::new(),
+                get_installed_guide(app_handle, guide_id)?,
+            ));
+        }
+    }
+
+    if include_profile {
+        bundle.profile = Some(collect_profile(app_handle, &exported_ids)?);
+    }
+
+    let path = write_bundle(PathBuf::from(path), &bundle)?;
+
+    Ok(BundleExport {
+        path: path.to_string_lossy().to_string(),
+        guides_count: bundle.guides.len() as u32,
+    })
+}
+
+/// Merge a `.ganymede` file into the library, under `target_folder`. Progress, step notes and
+/// pins of the bundle are only applied to the active profile where it has none of its own.
+/// Local guides are given new ids, since ids of local guides from other machines are unrelated to
+/// the installed ones.
+pub fn import_bundle<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    path: String,
+    target_folder: String,
+    conflict: BundleConflictStrategy,
+    include_profile: bool,
+) -> Result<BundleImport, Error> {
+    info!(
+        "[Bundle] importing {} into {:?} ({:?})",
+        path, target_folder, conflict
+    );
+
+    let bundle = read_bundle(Path::new(&path))?;
+    let guides_dir = app_handle.path().app_guides_dir();
+    let target = resolve_folder(&guides_dir, &target_folder)?;
+
+    // validate every path before touching the library
+    let folders = bundle
+        .folders
+        .iter()
+        .map(|folder| resolve_folder(&target, folder))
+        .collect::<Result<Vec<_>, _>>()?;
+    let guides = bundle
+        .guides
+        .into_iter()
+        .map(|bundled| {
+            let mut guide = bundled.guide;
+
+            guide.folder = Some(resolve_folder(&target, &bundled.folder)?);
+
+            Ok(guide)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let library = app_handle.state::<GuidesLibrary>();
+
+    for folder in [&target].into_iter().chain(&folders) {
+        library.record_app_change(folder);
+        fs::create_dir_all(folder).map_err(|err| Error::CreateGuidesDir(err.to_string()))?;
+    }
+
+    let installed_guides = get_guides_from_handle(app_handle, "".to_string())?.guides;
+    let used_ids = used_guide_ids(app_handle, &installed_guides);
+    let plan = plan_import(guides, &installed_guides, used_ids, &conflict)?;
+
+    for (guide, installed) in plan.guides {
+        if let Some(folder) = &guide.folder {
+            library.record_app_change(folder);
+            fs::create_dir_all(folder).map_err(|err| Error::CreateGuidesDir(err.to_string()))?;
+        }
+
+        let Some(installed) = installed else {
+            debug!("[Bundle] importing guide {}", guide.id);
+
+            write_guides(
+                &Guides {
+                    guides: vec![guide],
+                },
+                app_handle,
+            )?;
+
+            continue;
+        };
+
+        debug!("[Bundle] replacing installed guide {}", guide.id);
+
+        if let Err(err) =
+            crate::guide_versions::snapshot_replaced_guide(app_handle, &installed, &guide)
+        {
+            warn!(
+                "[Bundle] failed to save previous version of guide {}: {}",
+                guide.id, err
+            );
+        }
+
+        let moved = guide.folder != installed.folder;
+
+        write_guide_and_remap(app_handle, &installed, guide)?;
+
+        if moved {
+            if let Some(folder) = &installed.folder {
+                let file = folder.join(format!("{}.json", installed.id));
+
+                library.record_app_change(&file);
+                crate::storage::remove(&file)
+                    .map_err(|err| Error::DeleteGuideFileInSystem(err.to_string()))?;
+            }
+        }
+    }
+
+    let result = plan.result;
+
+    if include_profile {
+        if let Some(mut profile) = bundle.profile {
+            profile.remap_guide_ids(&plan.new_ids);
+
+            let imported = result
+                .imported
+                .iter()
+                .chain(&result.replaced)
+                .copied()
+                .collect::<HashSet<_>>();
+
+            merge_profile(app_handle, profile, &imported)?;
+        }
+    }
+
+    Ok(result)
+}
+
+// Private Functions
+
+/// Write a bundle to `path`, with the bundle extension added when missing. Returns the path
+/// written.
+fn write_bundle(mut path: PathBuf, bundle: &GuideBundle) -> Result<PathBuf, Error> {
+    if path.extension().is_none_or(|ext| ext != BUNDLE_EXTENSION) {
+        path.as_mut_os_string()
+            .push(format!(".{}", BUNDLE_EXTENSION));
+    }
+
+    let json = crate::json::serialize_pretty(bundle).map_err(Error::SerializeGuide)?;
+
+    crate::storage::write(&path, json).map_err(|err| Error::WriteBundle(err.to_string()))?;
+
+    Ok(path)
+}
+
+fn read_bundle(path: &Path) -> Result<GuideBundle, Error> {
+    let content = fs::read_to_string(path).map_err(|err| Error::ReadBundle(err.to_string()))?;
+    let bundle = crate::json::from_str::<GuideBundle>(&content).map_err(Error::BundleMalformed)?;
+
+    if bundle.version > BUNDLE_FORMAT_VERSION {
+        return Err(Error::UnsupportedBundleVersion(bundle.version));
+    }
+
+    Ok(bundle)
+}
+
+/// Decide which guides of a bundle are written following the conflict strategy, the local guides
+/// are given ids not in `used_ids`.
+fn plan_import(
+    guides: Vec<GuideWithSteps>,
+    installed_guides: &[GuideWithSteps],
+    mut used_ids: Vec<u32>,
+    conflict: &BundleConflictStrategy,
+) -> Result<ImportPlan, Error> {
+    let mut plan = ImportPlan::default();
+    let mut handled_ids = HashSet::new();
+
+    for mut guide in guides {
+        if is_local_guide(guide.id) {
+            guide.id = match plan.new_ids.get(&guide.id) {
+                Some(id) => *id,
+                None => {
+                    let id = next_local_guide_id(used_ids.iter().copied())?;
+
+                    used_ids.push(id);
+                    plan.new_ids.insert(guide.id, id);
+
+                    id
+                }
+            };
+        }
+
+        if !handled_ids.insert(guide.id) {
+            continue;
+        }
+
+        let Some(installed) = installed_guides.iter().find(|g| g.id == guide.id) else {
+            plan.result.imported.push(guide.id);
+            plan.guides.push((guide, None));
+
+            continue;
+        };
+
+        let replace = match conflict {
+            BundleConflictStrategy::Skip => false,
+            BundleConflictStrategy::Replace => true,
+            BundleConflictStrategy::KeepNewest => guide.updated_at > installed.updated_at,
+        };
+
+        if replace {
+            plan.result.replaced.push(guide.id);
+            plan.guides.push((guide, Some(installed.clone())));
+        } else {
+            debug!("[Bundle] keeping installed guide {}", guide.id);
+
+            plan.result.skipped.push(guide.id);
+        }
+    }
+
+    Ok(plan)
+}
+
+/// `dir` and all its subdirectories.
+fn list_dirs(dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let mut dirs = vec![dir.to_path_buf()];
+    let mut index = 0;
+
+    while index < dirs.len() {
+        for entry in
+            fs::read_dir(&dirs[index]).map_err(|err| Error::ReadGuidesDir(err.to_string()))?
+        {
+            let path = entry
+                .map_err(|err| Error::ReadGuidesDir(err.to_string()))?
+                .path();
+
+            if path.is_dir() {
+                dirs.push(path);
+            }
+        }
+
+        index += 1;
+    }
+
+    Ok(dirs)
+}
+
+fn relative_folder(base: &Path, folder: &Path) -> String {
+    folder
+        .strip_prefix(base)
+        .unwrap_or(folder)
+        .components()
+        .filter_map(|component| component.as_os_str().to_str())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn collect_profile<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_ids: &HashSet<u32>,
+) -> Result<BundledProfile, Error> {
+    let conf = crate::conf::get_conf(app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+    let Some(profile) = conf.profiles.iter().find(|p| p.id == conf.profile_in_use) else {
+        return Ok(BundledProfile::default());
+    };
+
+    let step_notes = crate::step_notes::get_step_notes(app_handle)
+        .map_err(|err| Error::Profile(err.to_string()))?
+        .profiles
+        .remove(&profile.id)
+        .map(|notes| {
+            notes
+                .guides
+                .into_iter()
+                .filter(|(guide_id, _)| guide_ids.contains(guide_id))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let pinned_guides = crate::pinned_guides::get_pinned_guides(app_handle)
+        .map_err(|err| Error::Profile(err.to_string()))?
+        .profiles
+        .remove(&profile.id)
+        .map(|pinned| {
+            pinned
+                .guides
+                .into_iter()
+                .filter(|guide_id| guide_ids.contains(guide_id))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(BundledProfile {
+        progresses: profile
+            .progresses
+            .iter()
+            .filter(|progress| guide_ids.contains(&progress.id))
+            .cloned()
+            .collect(),
+        step_notes,
+        pinned_guides,
+    })
+}
+
+fn merge_profile<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    bundled: BundledProfile,
+    guide_ids: &HashSet<u32>,
+) -> Result<(), Error> {
+    let mut conf =
+        crate::conf::get_conf(app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+    let profile_id = conf.profile_in_use.clone();
+    let Some(profile) = conf.profiles.iter_mut().find(|p| p.id == profile_id) else {
+        return Ok(());
+    };
+
+    for progress in bundled.progresses {
+        if guide_ids.contains(&progress.id)
+            && !profile.progresses.iter().any(|p| p.id == progress.id)
+        {
+            profile.progresses.push(progress);
+        }
+    }
+
+    crate::conf::save_conf(&mut conf, app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+
+    let mut notes = crate::step_notes::get_step_notes(app_handle)
+        .map_err(|err| Error::Profile(err.to_string()))?;
+    let profile_notes = notes.profiles.entry(profile_id.clone()).or_default();
+
+    for (guide_id, guide_notes) in bundled.step_notes {
+        if guide_ids.contains(&guide_id) {
+            profile_notes.guides.entry(guide_id).or_insert(guide_notes);
+        }
+    }
+
+    crate::step_notes::save_step_notes(&notes, app_handle)
+        .map_err(|err| Error::Profile(err.to_string()))?;
+
+    let mut pinned = crate::pinned_guides::get_pinned_guides(app_handle)
+        .map_err(|err| Error::Profile(err.to_string()))?;
+    let profile_pinned = pinned.profiles.entry(profile_id).or_default();
+
+    for guide_id in bundled.pinned_guides {
+        if profile_pinned.guides.len() >= MAX_PINNED_PER_PROFILE {
+            break;
+        }
+
+        if guide_ids.contains(&guide_id) && !profile_pinned.guides.contains(&guide_id) {
+            profile_pinned.guides.push(guide_id);
+        }
+    }
+
+    crate::pinned_guides::save_pinned_guides(&pinned, app_handle)
+        .map_err(|err| Error::Profile(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, path::Path};
+
+    use super::{
+        plan_import, read_bundle, relative_folder, write_bundle, BundleConflictStrategy,
+        BundledGuide, BundledProfile, GuideBundle, BUNDLE_FORMAT_VERSION,
+    };
+    use crate::{
+        conf::Progress,
+        guides::{GuideWithSteps, LOCAL_GUIDE_ID_START},
+        test_guides::{guide, step},
+    };
+
+    /// Write a bundle of `guides` and read it back, as an export followed by an import.
+    fn round_trip(guides: Vec<GuideWithSteps>) -> Vec<GuideWithSteps> {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle = GuideBundle {
+            version: BUNDLE_FORMAT_VERSION,
+            exported_at: "2024-01-01T00:00:00Z".to_string(),
+            folders: vec![],
+            guides: guides
+                .into_iter()
+                .map(|guide| BundledGuide::new("".to_string(), guide))
+                .collect(),
+            profile: None,
+        };
+
+        let path = write_bundle(dir.path().join("guides"), &bundle).unwrap();
+
+        assert_eq!(path, dir.path().join("guides.ganymede"));
+
+        read_bundle(&path)
+            .unwrap()
+            .guides
+            .into_iter()
+            .map(|bundled| bundled.guide)
+            .collect()
+    }
+
+    #[test]
+    fn import_applies_the_conflict_strategy() {
+        let installed = vec![guide(1).updated_at("2024-02-01").build()];
+        let mut outdated = guide(1)
+            .updated_at("2024-01-01")
+            .steps(vec![step("a")])
+            .build();
+
+        outdated.folder = Some("/elsewhere".into());
+
+        let bundled = round_trip(vec![outdated, guide(2).build()]);
+
+        assert_eq!(bundled[0].folder, None);
+        assert_eq!(bundled[0].steps[0].web_text, "a");
+
+        let skip = plan_import(
+            bundled.clone(),
+            &installed,
+            vec![1],
+            &BundleConflictStrategy::Skip,
+        )
+        .unwrap();
+
+        assert_eq!(skip.result.imported, vec![2]);
+        assert_eq!(skip.result.skipped, vec![1]);
+        assert!(skip.result.replaced.is_empty());
+        assert_eq!(skip.guides.len(), 1);
+
+        let replace = plan_import(
+            bundled.clone(),
+            &installed,
+            vec![1],
+            &BundleConflictStrategy::Replace,
+        )
+        .unwrap();
+
+        assert_eq!(replace.result.replaced, vec![1]);
+        assert_eq!(replace.result.imported, vec![2]);
+        assert_eq!(replace.guides[0].1.as_ref().unwrap().id, 1);
+
+        let keep_newest = plan_import(
+            bundled,
+            &installed,
+            vec![1],
+            &BundleConflictStrategy::KeepNewest,
+        )
+        .unwrap();
+
+        assert_eq!(keep_newest.result.skipped, vec![1]);
+
+        let newer = round_trip(vec![guide(1).updated_at("2024-03-01").build()]);
+        let keep_newest = plan_import(
+            newer,
+            &installed,
+            vec![1],
+            &BundleConflictStrategy::KeepNewest,
+        )
+        .unwrap();
+
+        assert_eq!(keep_newest.result.replaced, vec![1]);
+    }
+
+    #[test]
+    fn import_gives_local_guides_new_ids() {
+        let local = LOCAL_GUIDE_ID_START;
+        let installed = vec![guide(local).build()];
+        let bundled = round_trip(vec![guide(local).build(), guide(local + 1).build()]);
+
+        let plan = plan_import(
+            bundled,
+            &installed,
+            vec![local, local + 2],
+            &BundleConflictStrategy::Replace,
+        )
+        .unwrap();
+
+        // never the id of an installed guide, even when the bundled guide would replace it
+        assert_eq!(plan.result.imported, vec![local + 3, local + 4]);
+        assert!(plan.result.replaced.is_empty());
+        assert_eq!(plan.new_ids[&local], local + 3);
+        assert_eq!(plan.new_ids[&(local + 1)], local + 4);
+
+        let mut profile = BundledProfile {
+            progresses: vec![Progress {
+                id: local,
+                current_step: 3,
+                steps: HashMap::new(),
+                updated_at: None,
+            }],
+            step_notes: HashMap::new(),
+            pinned_guides: vec![local + 1, 1],
+        };
+
+        profile.remap_guide_ids(&plan.new_ids);
+
+        assert_eq!(profile.progresses[0].id, local + 3);
+        assert_eq!(profile.pinned_guides, vec![local + 4, 1]);
+    }
+
+    #[test]
+    fn relative_folder_uses_forward_slashes() {
+        let base = Path::new("/config/guides");
+
+        assert_eq!(
+            relative_folder(base, &base.join("curated").join("frigost")),
+            "curated/frigost"
+        );
+        assert_eq!(relative_folder(base, base), "");
+    }
+}
@@ -0,0 +1,610 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    path::Path,
+    sync::Mutex,
+};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest::{self, header, StatusCode};
+
+use crate::{
+    api::GANYMEDE_API,
+    guides::{Error, GameType, Guide, GuideLang, Status},
+    storage::ReadError,
+    tauri_api_ext::CatalogPathExt,
+};
+
+// Constants
+
+const ALL_STATUSES_KEY: &str = "all";
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+// Enums
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum GuideSort {
+    /// Most liked first.
+    Likes,
+    /// Most downloaded first.
+    Downloads,
+    /// Most recently updated first.
+    UpdatedAt,
+    /// Ascending `order`, as ordered by the authors.
+    Order,
+}
+
+// Structs
+
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideCatalog {
+    pub guides: Vec<Guide>,
+    /// The server could not be reached, the guides are the ones of the last successful request.
+    pub stale: bool,
+    pub fetched_at: String,
+    /// Guides missing from the catalog when it was last visited, empty before the first visit.
+    pub new_guide_ids: Vec<u32>,
+    /// Guides whose `updated_at` changed since the catalog was last visited.
+    pub updated_guide_ids: Vec<u32>,
+}
+
+/// Filters, sort and page of a catalog query, every filter is optional.
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+#[serde(default, rename_all = "camelCase")]
+pub struct GuideQuery {
+    pub status: Option<Status>,
+    pub lang: Option<GuideLang>,
+    pub game_type: Option<GameType>,
+    pub user_id: Option<u32>,
+    /// Case-insensitive text searched in the guide names.
+    pub search: Option<String>,
+    pub sort: Option<GuideSort>,
+    /// Starts at 1.
+    pub page: u32,
+    /// Guides per page, 50 when 0.
+    pub per_page: u32,
+}
+
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuidesPage {
+    pub guides: Vec<Guide>,
+    pub page: u32,
+    pub per_page: u32,
+    /// Guides matching the query across every page, when known.
+    pub total: Option<u32>,
+    pub has_more: bool,
+    /// The server could not be reached, the guides come from the last fetched catalog.
+    pub stale: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedCatalog {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: String,
+    guides: Vec<Guide>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogVisit {
+    visited_at: String,
+    /// `updated_at` of every guide of the catalog when it was visited.
+    guides: HashMap<u32, Option<String>>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CatalogCache {
+    /// Catalogs by requested status.
+    catalogs: HashMap<String, CachedCatalog>,
+    last_visit: Option<CatalogVisit>,
+}
+
+/// Persistent copy of the server catalog, revalidated with `ETag` and `Last-Modified` and loaded
+/// from the disk on first use.
+#[derive(Default)]
+pub struct GuideCatalogCache {
+    cache: Mutex<Option<CatalogCache>>,
+}
+
+// Implementations
+
+impl GuideQuery {
+    fn page(&self) -> u32 {
+        self.page.max(1)
+    }
+
+    fn per_page(&self) -> u32 {
+        if self.per_page == 0 {
+            DEFAULT_PAGE_SIZE
+        } else {
+            self.per_page
+        }
+    }
+
+    fn matches(&self, guide: &Guide) -> bool {
+        self.status
+            .as_ref()
+            .is_none_or(|status| *status == guide.status)
+            && self.lang.as_ref().is_none_or(|lang| *lang == guide.lang)
+            && self
+                .game_type
+                .as_ref()
+                .is_none_or(|game_type| *game_type == guide.game_type)
+            && self.user_id.is_none_or(|user_id| user_id == guide.user_id)
+            && self.search.as_ref().is_none_or(|search| {
+                guide
+                    .name
+                    .to_lowercase()
+                    .contains(search.trim().to_lowercase().as_str())
+            })
+    }
+
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![];
+
+        if let Some(status) = &self.status {
+            params.push(("status", status.to_str().to_string()));
+        }
+        if let Some(lang) = &self.lang {
+            params.push(("lang", lang.to_str().to_string()));
+        }
+        if let Some(game_type) = &self.game_type {
+            params.push(("game_type", game_type.to_str().to_string()));
+        }
+        if let Some(user_id) = self.user_id {
+            params.push(("user_id", user_id.to_string()));
+        }
+        if let Some(search) = self
+            .search
+            .as_ref()
+            .filter(|search| !search.trim().is_empty())
+        {
+            params.push(("search", search.trim().to_string()));
+        }
+        if let Some(sort) = &self.sort {
+            params.push(("sort", sort.to_str().to_string()));
+        }
+
+        params.push(("page", self.page().to_string()));
+        params.push(("per_page", self.per_page().to_string()));
+
+        params
+    }
+}
+
+impl GuideSort {
+    pub fn to_str(&self) -> &'static str {
+        use GuideSort::*;
+
+        match self {
+            Likes => "likes",
+            Downloads => "downloads",
+            UpdatedAt => "updated_at",
+            Order => "order",
+        }
+    }
+
+    fn compare(&self, a: &Guide, b: &Guide) -> Ordering {
+        use GuideSort::*;
+
+        match self {
+            Likes => b.likes.cmp(&a.likes),
+            Downloads => b.downloads.cmp(&a.downloads),
+            UpdatedAt => b.updated_at.cmp(&a.updated_at),
+            Order => a.order.cmp(&b.order),
+        }
+    }
+}
+
+impl GuideCatalogCache {
+    fn with_cache<T>(&self, file: &Path, f: impl FnOnce(&mut CatalogCache) -> T) -> T {
+        let mut lock = self.cache.lock().unwrap();
+        let cache = lock.get_or_insert_with(|| read_cache(file));
+
+        f(cache)
+    }
+}
+
+// Public Functions
+
+/// Guides of the server catalog, only downloaded when they changed since the last request. The
+/// cached catalog is returned, flagged as stale, when the server cannot be reached.
+pub async fn get_catalog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    status: Option<Status>,
+) -> Result<GuideCatalog, Error> {
+    let file = app_handle.path().app_catalog_file();
+    let state = app_handle.state::<GuideCatalogCache>();
+    let key = status
+        .as_ref()
+        .map_or(ALL_STATUSES_KEY, |status| status.to_str())
+        .to_string();
+    let cached = state.with_cache(&file, |cache| cache.catalogs.get(&key).cloned());
+
+    let (catalog, stale) = match request_catalog(app_handle, status, cached.as_ref()).await {
+        Ok(Some(catalog)) => {
+            state.with_cache(&file, |cache| {
+                cache.catalogs.insert(key, catalog.clone());
+                save_cache(&file, cache);
+            });
+
+            (catalog, false)
+        }
+        Ok(None) => {
+            debug!("[GuideCatalog] catalog {} not modified", key);
+
+            (
+                cached.expect("[GuideCatalog] not modified without cache"),
+                false,
+            )
+        }
+        Err(err @ (Error::NetworkUnavailable | Error::RequestGuides(_))) => match cached {
+            Some(cached) => {
+                warn!("[GuideCatalog] serving stale catalog {}: {}", key, err);
+
+                (cached, true)
+            }
+            None => return Err(err),
+        },
+        Err(err) => return Err(err),
+    };
+
+    let (new_guide_ids, updated_guide_ids) = state.with_cache(&file, |cache| {
+        cache
+            .last_visit
+            .as_ref()
+            .map(|visit| changes_since_visit(&catalog.guides, visit))
+            .unwrap_or_default()
+    });
+
+    Ok(GuideCatalog {
+        guides: catalog.guides,
+        stale,
+        fetched_at: catalog.fetched_at,
+        new_guide_ids,
+        updated_guide_ids,
+    })
+}
+
+/// Remember the guides of the cached catalogs, the next catalogs report the guides added or
+/// updated since.
+pub fn mark_catalog_visited<R: Runtime>(app_handle: &AppHandle<R>) {
+    let file = app_handle.path().app_catalog_file();
+
+    app_handle
+        .state::<GuideCatalogCache>()
+        .with_cache(&file, |cache| {
+            let guides = cache
+                .catalogs
+                .values()
+                .flat_map(|catalog| &catalog.guides)
+                .map(|guide| (guide.id, guide.updated_at.clone()))
+                .collect();
+
+            cache.last_visit = Some(CatalogVisit {
+                visited_at: chrono::Utc::now().to_rfc3339(),
+                guides,
+            });
+
+            save_cache(&file, cache);
+        });
+}
+
+/// One page of the server guides matching `query`. The filters, sort and pagination are sent to
+/// the server, when it ignores some of them the query is applied to the cached catalog instead.
+pub async fn query_guides<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    query: GuideQuery,
+) -> Result<GuidesPage, Error> {
+    debug!("[GuideCatalog] query {:?}", query);
+
+    match request_guides_page(app_handle, &query).await {
+        Ok((guides, _)) if guides.len() as u32 > query.per_page() => {
+            debug!("[GuideCatalog] server ignored pagination, paginating the response");
+
+            Ok(apply_query(guides, &query, false))
+        }
+        Ok((guides, total)) if answers_query(&guides, &query) => {
+            let has_more = match total {
+                Some(total) => (query.page() as u64) * (query.per_page() as u64) < total as u64,
+                None => guides.len() as u32 == query.per_page(),
+            };
+
+            Ok(GuidesPage {
+                guides,
+                page: query.page(),
+                per_page: query.per_page(),
+                total,
+                has_more,
+                stale: false,
+            })
+        }
+        Ok(_) => {
+            debug!("[GuideCatalog] server ignored filters, querying the catalog");
+
+            let catalog = get_catalog(app_handle, query.status.clone()).await?;
+
+            Ok(apply_query(catalog.guides, &query, catalog.stale))
+        }
+        Err(err) => {
+            warn!("[GuideCatalog] query failed, querying the catalog: {}", err);
+
+            let catalog = get_catalog(app_handle, query.status.clone()).await?;
+
+            Ok(apply_query(catalog.guides, &query, catalog.stale))
+        }
+    }
+}
+
+// Private Functions
+
+/// Request one page of guides, with the total count when the server sends it.
+async fn request_guides_page<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    query: &GuideQuery,
+) -> Result<(Vec<Guide>, Option<u32>), Error> {
+    let http_client = app_handle.state::<reqwest::Client>();
+    let params = query
+        .query_params()
+        .into_iter()
+        .map(|(name, value)| format!("{}={}", name, urlencoding::encode(&value)))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    let res = http_client
+        .get(format!("{}/v2/guides?{}", GANYMEDE_API, params))
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|err| Error::RequestGuides(err.to_string()))?;
+
+    let total = res
+        .headers()
+        .get(TOTAL_COUNT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+
+    let text = res
+        .text()
+        .await
+        .map_err(|err| Error::RequestGuidesContent(err.to_string()))?;
+    let guides =
+        crate::json::from_str::<Vec<Guide>>(text.as_str()).map_err(Error::GuidesMalformed)?;
+
+    Ok((guides, total))
+}
+
+/// Whether every guide matches the filters of `query` and they are in its order.
+fn answers_query(guides: &[Guide], query: &GuideQuery) -> bool {
+    guides.iter().all(|guide| query.matches(guide))
+        && query
+            .sort
+            .as_ref()
+            .is_none_or(|sort| guides.is_sorted_by(|a, b| sort.compare(a, b) != Ordering::Greater))
+}
+
+fn apply_query(guides: Vec<Guide>, query: &GuideQuery, stale: bool) -> GuidesPage {
+    let mut guides = guides
+        .into_iter()
+        .filter(|guide| query.matches(guide))
+        .collect::<Vec<_>>();
+
+    if let Some(sort) = &query.sort {
+        guides.sort_by(|a, b| sort.compare(a, b));
+    }
+
+    let total = guides.len() as u32;
+    let start = (query.page() as usize - 1).saturating_mul(query.per_page() as usize);
+    let guides = guides
+        .into_iter()
+        .skip(start)
+        .take(query.per_page() as usize)
+        .collect::<Vec<_>>();
+
+    GuidesPage {
+        has_more: start + guides.len() < total as usize,
+        guides,
+        page: query.page(),
+        per_page: query.per_page(),
+        total: Some(total),
+        stale,
+    }
+}
+
+/// Request the catalog, `None` when the server answers it did not change since `cached`.
+async fn request_catalog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    status: Option<Status>,
+    cached: Option<&CachedCatalog>,
+) -> Result<Option<CachedCatalog>, Error> {
+    let http_client = app_handle.state::<reqwest::Client>();
+
+    let url = if let Some(status) = status {
+        format!("{}/v2/guides?status={}", GANYMEDE_API, status.to_str())
+    } else {
+        format!("{}/v2/guides", GANYMEDE_API)
+    };
+
+    let mut request = http_client.get(url);
+
+    if let Some(cached) = cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let res = request.send().await.map_err(|err| {
+        if err.is_connect() || err.is_timeout() {
+            Error::NetworkUnavailable
+        } else {
+            Error::RequestGuides(err.to_string())
+        }
+    })?;
+
+    if res.status() == StatusCode::NOT_MODIFIED && cached.is_some() {
+        return Ok(None);
+    }
+
+    if !res.status().is_success() {
+        return Err(Error::RequestGuides(res.status().to_string()));
+    }
+
+    let header_value = |name: header::HeaderName| {
+        res.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let etag = header_value(header::ETAG);
+    let last_modified = header_value(header::LAST_MODIFIED);
+
+    let text = res
+        .text()
+        .await
+        .map_err(|err| Error::RequestGuidesContent(err.to_string()))?;
+    let guides =
+        crate::json::from_str::<Vec<Guide>>(text.as_str()).map_err(Error::GuidesMalformed)?;
+
+    info!(
+        "[GuideCatalog] downloaded catalog of {} guides",
+        guides.len()
+    );
+
+    Ok(Some(CachedCatalog {
+        etag,
+        last_modified,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        guides,
+    }))
+}
+
+fn changes_since_visit(guides: &[Guide], visit: &CatalogVisit) -> (Vec<u32>, Vec<u32>) {
+    let mut new_guide_ids = BTreeSet::new();
+    let mut updated_guide_ids = BTreeSet::new();
+
+    for guide in guides {
+        match visit.guides.get(&guide.id) {
+            None => {
+                new_guide_ids.insert(guide.id);
+            }
+            Some(updated_at) if *updated_at != guide.updated_at => {
+                updated_guide_ids.insert(guide.id);
+            }
+            Some(_) => {}
+        }
+    }
+
+    (
+        new_guide_ids.into_iter().collect(),
+        updated_guide_ids.into_iter().collect(),
+    )
+}
+
+fn read_cache(file: &Path) -> CatalogCache {
+    match crate::storage::read_json::<CatalogCache>(file) {
+        Ok(cache) => cache.unwrap_or_default(),
+        Err(ReadError::Io(err)) => {
+            warn!("[GuideCatalog] cannot read the catalog cache: {}", err);
+            CatalogCache::default()
+        }
+        Err(ReadError::Malformed(err)) => {
+            warn!("[GuideCatalog] malformed catalog cache: {}", err);
+            CatalogCache::default()
+        }
+    }
+}
+
+fn save_cache(file: &Path, cache: &CatalogCache) {
+    let result = crate::json::serialize_pretty(cache)
+        .map_err(|err| err.to_string())
+        .and_then(|json| {
+            if let Some(dir) = file.parent() {
+                std::fs::create_dir_all(dir).map_err(|err| err.to_string())?;
+            }
+
+            crate::storage::write(file, json).map_err(|err| err.to_string())
+        });
+
+    if let Err(err) = result {
+        warn!("[GuideCatalog] cannot save the catalog cache: {}", err);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        answers_query, apply_query, changes_since_visit, CatalogVisit, GuideQuery, GuideSort,
+    };
+    use crate::guides::{Guide, GuideLang};
+
+    /// Guide of the catalog, the higher its id the more it is liked.
+    fn guide(id: u32, updated_at: &str) -> Guide {
+        crate::test_guides::guide(id)
+            .likes(id)
+            .updated_at(updated_at)
+            .summary()
+    }
+
+    #[test]
+    fn changes_since_visit_splits_new_and_updated_guides() {
+        let visit = CatalogVisit {
+            visited_at: String::new(),
+            guides: HashMap::from([(1, Some("a".to_string())), (2, Some("b".to_string()))]),
+        };
+
+        let (new_guide_ids, updated_guide_ids) =
+            changes_since_visit(&[guide(1, "a"), guide(2, "c"), guide(3, "d")], &visit);
+
+        assert_eq!(new_guide_ids, vec![3]);
+        assert_eq!(updated_guide_ids, vec![2]);
+    }
+
+    #[test]
+    fn apply_query_filters_sorts_and_paginates() {
+        let mut english = guide(4, "e");
+        english.lang = GuideLang::En;
+        english.name = "Dopple".to_string();
+
+        let guides = vec![guide(1, "a"), guide(2, "b"), guide(3, "c"), english];
+        let query = GuideQuery {
+            lang: Some(GuideLang::Fr),
+            sort: Some(GuideSort::Likes),
+            page: 2,
+            per_page: 2,
+            ..Default::default()
+        };
+
+        let page = apply_query(guides.clone(), &query, false);
+
+        assert_eq!(
+            page.guides.iter().map(|g| g.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(page.total, Some(3));
+        assert!(!page.has_more);
+        assert!(!answers_query(&guides, &query));
+
+        let search = GuideQuery {
+            search: Some(" dop ".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(apply_query(guides, &search, true).guides[0].id, 4);
+    }
+}
@@ -0,0 +1,167 @@
+use log::{info, warn};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    guide_diff::{StepChange, StepMapping},
+    guides::{Error, GuideWithSteps, GuidesEventTrigger},
+    storage::ReadError,
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Structs
+
+/// Steps changed by the update of a guide, `added` and `modified` are step indices in the new
+/// version and `removed` step indices in the previous one.
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideChangelogEntry {
+    pub guide_id: u32,
+    pub name: String,
+    pub previous_updated_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub added: Vec<u32>,
+    pub removed: Vec<u32>,
+    pub modified: Vec<u32>,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuidesChangelog {
+    pub created_at: String,
+    pub guides: Vec<GuideChangelogEntry>,
+}
+
+// Public Functions
+
+/// Changes of the guides whose `updated_at` changed between `previous_guides` and
+/// `updated_guides`, the guides which were not installed before are ignored.
+pub fn build_changelog(
+    previous_guides: &[GuideWithSteps],
+    updated_guides: &[GuideWithSteps],
+) -> Vec<GuideChangelogEntry> {
+    updated_guides
+        .iter()
+        .filter_map(|guide| {
+            let previous = previous_guides.iter().find(|g| g.id == guide.id)?;
+
+            if previous.updated_at == guide.updated_at {
+                return None;
+            }
+
+            let mut entry = GuideChangelogEntry {
+                guide_id: guide.id,
+                name: guide.name.clone(),
+                previous_updated_at: previous.updated_at.clone(),
+                updated_at: guide.updated_at.clone(),
+                added: vec![],
+                removed: vec![],
+                modified: vec![],
+            };
+
+            for change in StepMapping::between(&previous.steps, &guide.steps).changes() {
+                match change {
+                    StepChange::Added { to } => entry.added.push(to),
+                    StepChange::Removed { from } => entry.removed.push(from),
+                    StepChange::Modified { to, .. } => entry.modified.push(to),
+                    StepChange::Unchanged { .. } => {}
+                }
+            }
+
+            Some(entry)
+        })
+        .collect()
+}
+
+/// Save the changelog of an update as the last one and emit `guidesUpdated`, nothing is done when
+/// no guide changed.
+pub fn publish_changelog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    previous_guides: &[GuideWithSteps],
+    updated_guides: &[GuideWithSteps],
+) {
+    let guides = build_changelog(previous_guides, updated_guides);
+
+    if guides.is_empty() {
+        return;
+    }
+
+    info!("[GuideChangelog] {} guides updated", guides.len());
+
+    let changelog = GuidesChangelog {
+        created_at: chrono::Utc::now().to_rfc3339(),
+        guides,
+    };
+
+    if let Err(err) = save_changelog(app_handle, &changelog) {
+        warn!("[GuideChangelog] failed to save the changelog: {}", err);
+    }
+
+    if let Err(err) = GuidesEventTrigger::new(app_handle.clone()).guides_updated(changelog) {
+        warn!(
+            "[GuideChangelog] failed to emit guides_updated event: {:?}",
+            err
+        );
+    }
+}
+
+/// Changelog of the last update which changed guides, `None` before the first one.
+pub fn get_last_changelog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Option<GuidesChangelog>, Error> {
+    let path = app_handle.path().app_guides_changelog_file();
+
+    match crate::storage::read_json::<GuidesChangelog>(&path) {
+        Ok(changelog) => Ok(changelog),
+        Err(ReadError::Io(err)) => Err(Error::ReadChangelog(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::ReadChangelog(err.to_string())),
+    }
+}
+
+// Private Functions
+
+fn save_changelog<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    changelog: &GuidesChangelog,
+) -> Result<(), Error> {
+    let json = crate::json::serialize_pretty(changelog).map_err(Error::SerializeGuide)?;
+
+    crate::storage::write(&app_handle.path().app_guides_changelog_file(), json)
+        .map_err(|err| Error::WriteChangelog(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_changelog;
+    use crate::{guides::GuideWithSteps, test_guides::step};
+
+    fn guide(id: u32, updated_at: &str, steps: &[&str]) -> GuideWithSteps {
+        crate::test_guides::guide(id)
+            .updated_at(updated_at)
+            .steps(steps.iter().map(|web_text| step(web_text)).collect())
+            .build()
+    }
+
+    #[test]
+    fn build_changelog_lists_step_changes_of_updated_guides() {
+        let previous = vec![
+            guide(1, "a", &["<p>one</p>", "<p>two</p>", "<p>three</p>"]),
+            guide(2, "b", &["<p>one</p>"]),
+        ];
+        let updated = vec![
+            guide(1, "c", &["<p>one</p>", "<p>three</p>", "<p>four</p>"]),
+            guide(2, "b", &["<p>one</p>"]),
+            guide(3, "d", &["<p>one</p>"]),
+        ];
+
+        let changelog = build_changelog(&previous, &updated);
+
+        assert_eq!(changelog.len(), 1);
+        assert_eq!(changelog[0].guide_id, 1);
+        assert_eq!(changelog[0].previous_updated_at.as_deref(), Some("a"));
+        assert_eq!(changelog[0].removed, vec![1]);
+        assert_eq!(changelog[0].added, vec![2]);
+        assert!(changelog[0].modified.is_empty());
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    conf::{ConfStep, Progress},
+    guides::GuideStep,
+    step_notes::GuideStepNotes,
+};
+
+// Enums
+
+/// Change of a step between two versions of a guide, `from` is the step index in the old version
+/// and `to` the step index in the new one.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StepChange {
+    Unchanged { from: u32, to: u32 },
+    Modified { from: u32, to: u32 },
+    Added { to: u32 },
+    Removed { from: u32 },
+}
+
+// Structs
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StepMatch {
+    pub index: u32,
+    /// `true` when the step is strictly identical, `false` when it was matched by its content or
+    /// its coordinates only.
+    pub exact: bool,
+}
+
+/// Matching between the steps of two versions of the same guide.
+#[derive(Debug)]
+pub struct StepMapping {
+    old_to_new: Vec<Option<StepMatch>>,
+    new_len: usize,
+}
+
+// Implementations
+
+impl StepMapping {
+    pub fn between(old: &[GuideStep], new: &[GuideStep]) -> Self {
+        let mut old_to_new = vec![None; old.len()];
+        let anchors = longest_common_steps(old, new);
+
+        // previous anchor (exclusive start of the gap) for the old and new guide
+        let mut old_start = 0;
+        let mut new_start = 0;
+
+        for &(old_anchor, new_anchor) in anchors
+            .iter()
+            .chain(std::iter::once(&(old.len(), new.len())))
+        {
+            match_gap(
+                old,
+                new,
+                old_start..old_anchor,
+                new_start..new_anchor,
+                &mut old_to_new,
+            );
+
+            if old_anchor < old.len() {
+                old_to_new[old_anchor] = Some(StepMatch {
+                    index: new_anchor as u32,
+                    exact: true,
+                });
+            }
+
+            old_start = old_anchor + 1;
+            new_start = new_anchor + 1;
+        }
+
+        StepMapping {
+            old_to_new,
+            new_len: new.len(),
+        }
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.old_to_new.len() == self.new_len
+            && self
+                .old_to_new
+                .iter()
+                .enumerate()
+                .all(|(index, m)| m.is_some_and(|m| m.exact && m.index as usize == index))
+    }
+
+    pub fn get(&self, old_index: u32) -> Option<StepMatch> {
+        self.old_to_new.get(old_index as usize).copied().flatten()
+    }
+
+    /// New index of a step, falling back to the closest previous step that still exists.
+    pub fn get_or_previous(&self, old_index: u32) -> u32 {
+        let end = (old_index as usize + 1).min(self.old_to_new.len());
+
+        self.old_to_new[..end]
+            .iter()
+            .rev()
+            .find_map(|m| m.map(|m| m.index))
+            .unwrap_or(0)
+    }
+
+    /// Old step indices which have no counterpart in the new guide.
+    pub fn unmapped(&self) -> Vec<u32> {
+        self.old_to_new
+            .iter()
+            .enumerate()
+            .filter(|(_, m)| m.is_none())
+            .map(|(index, _)| index as u32)
+            .collect()
+    }
+
+    /// Every step change, ordered as they appear when reading both versions side by side.
+    pub fn changes(&self) -> Vec<StepChange> {
+        let mut new_to_old = vec![None; self.new_len];
+
+        for (old_index, step_match) in self.old_to_new.iter().enumerate() {
+            if let Some(step_match) = step_match {
+                new_to_old[step_match.index as usize] = Some((old_index, step_match.exact));
+            }
+        }
+
+        let mut changes = vec![];
+        let mut next_old = 0;
+
+        let mut push_removed_until = |changes: &mut Vec<StepChange>, end: usize| {
+            while next_old < end {
+                if self.old_to_new[next_old].is_none() {
+                    changes.push(StepChange::Removed {
+                        from: next_old as u32,
+                    });
+                }
+
+                next_old += 1;
+            }
+        };
+
+        for (new_index, old) in new_to_old.into_iter().enumerate() {
+            let to = new_index as u32;
+
+            match old {
+                Some((old_index, exact)) => {
+                    push_removed_until(&mut changes, old_index + 1);
+
+                    let from = old_index as u32;
+
+                    changes.push(if exact {
+                        StepChange::Unchanged { from, to }
+                    } else {
+                        StepChange::Modified { from, to }
+                    });
+                }
+                None => changes.push(StepChange::Added { to }),
+            }
+        }
+
+        push_removed_until(&mut changes, self.old_to_new.len());
+
+        changes
+    }
+}
+
+// Public Functions
+
+/// Move the current step and the checked boxes of a progress to the new step indices.
+pub fn remap_progress(
+    progress: &mut Progress,
+    mapping: &StepMapping,
+    old_steps: &[GuideStep],
+    new_steps: &[GuideStep],
+) {
+    progress.current_step = mapping.get_or_previous(progress.current_step);
+
+    let mut steps = HashMap::new();
+
+    for (old_index, step) in progress.steps.drain() {
+        let Some(step_match) = mapping.get(old_index) else {
+            continue;
+        };
+
+        let step = if step_match.exact {
+            step
+        } else {
+            remap_checkboxes(
+                &step,
+                &old_steps[old_index as usize].web_text,
+                &new_steps[step_match.index as usize].web_text,
+            )
+        };
+
+        if !step.checkboxes.is_empty() {
+            steps.insert(step_match.index, step);
+        }
+    }
+
+    progress.steps = steps;
+}
+
+/// Move the notes of a guide to the new step indices. A note attached to a removed step is moved
+/// to the closest previous step when that step has no note yet, otherwise it is dropped.
+pub fn remap_step_notes(notes: &mut GuideStepNotes, mapping: &StepMapping) {
+    let mut steps = HashMap::new();
+    let mut orphans = vec![];
+
+    for (old_index, note) in notes.steps.drain() {
+        match mapping.get(old_index) {
+            Some(step_match) => {
+                steps.insert(step_match.index, note);
+            }
+            None => orphans.push((old_index, note)),
+        }
+    }
+
+    orphans.sort_by_key(|(old_index, _)| *old_index);
+
+    for (old_index, note) in orphans {
+        steps
+            .entry(mapping.get_or_previous(old_index))
+            .or_insert(note);
+    }
+
+    notes.steps = steps;
+}
+
+// Private Functions
+
+fn same_content(a: &GuideStep, b: &GuideStep) -> bool {
+    a.name == b.name && a.web_text == b.web_text
+}
+
+fn same_place(a: &GuideStep, b: &GuideStep) -> bool {
+    a.map == b.map && a.pos_x == b.pos_x && a.pos_y == b.pos_y
+}
+
+fn same_step(a: &GuideStep, b: &GuideStep) -> bool {
+    same_content(a, b) && same_place(a, b)
+}
+
+/// Longest common subsequence of strictly identical steps, as `(old_index, new_index)` pairs.
+fn longest_common_steps(old: &[GuideStep], new: &[GuideStep]) -> Vec<(usize, usize)> {
+    let (n, m) = (old.len(), new.len());
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if same_step(&old[i], &new[j]) {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+
+    let mut pairs = vec![];
+    let (mut i, mut j) = (0, 0);
+
+    while i < n && j < m {
+        if same_step(&old[i], &new[j]) {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    pairs
+}
+
+/// Pair the steps left between two identical steps, first by content then by coordinates, keeping
+/// the steps order.
+fn match_gap(
+    old: &[GuideStep],
+    new: &[GuideStep],
+    old_range: std::ops::Range<usize>,
+    new_range: std::ops::Range<usize>,
+    old_to_new: &mut [Option<StepMatch>],
+) {
+    let mut next_new = new_range.start;
+
+    for old_index in old_range {
+        let found = (next_new..new_range.end)
+            .find(|&new_index| same_content(&old[old_index], &new[new_index]))
+            .or_else(|| {
+                (next_new..new_range.end)
+                    .find(|&new_index| same_place(&old[old_index], &new[new_index]))
+            });
+
+        if let Some(new_index) = found {
+            old_to_new[old_index] = Some(StepMatch {
+                index: new_index as u32,
+                exact: false,
+            });
+            next_new = new_index + 1;
+        }
+    }
+}
+
+/// Checkbox labels of a step, in the order the checkboxes are rendered.
+fn checkbox_labels(web_text: &str) -> Vec<String> {
+    let document = scraper::Html::parse_fragment(web_text);
+    let checkbox_selector = scraper::Selector::parse("input[type='checkbox']").unwrap();
+
+    document
+        .select(&checkbox_selector)
+        .map(|checkbox| {
+            checkbox
+                .ancestors()
+                .filter_map(scraper::ElementRef::wrap)
+                .find(|element| element.value().name() == "li")
+                .map(|item| crate::search::html_to_plain_text(&item.html()))
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn remap_checkboxes(step: &ConfStep, old_web_text: &str, new_web_text: &str) -> ConfStep {
+    let old_labels = checkbox_labels(old_web_text);
+    let new_labels = checkbox_labels(new_web_text);
+    let mut used = vec![false; new_labels.len()];
+    let mut checkboxes = vec![];
+
+    for &checkbox in &step.checkboxes {
+        let Some(label) = old_labels.get(checkbox as usize) else {
+            continue;
+        };
+
+        let found =
+            (0..new_labels.len()).find(|&index| !used[index] && new_labels[index] == *label);
+
+        if let Some(index) = found {
+            used[index] = true;
+            checkboxes.push(index as u32);
+        }
+    }
+
+    ConfStep { checkboxes }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{remap_progress, StepChange, StepMapping};
+    use crate::{
+        conf::{ConfStep, Progress},
+        test_guides::step_at,
+    };
+
+    #[test]
+    fn step_mapping_handles_inserted_and_removed_steps() {
+        let old = vec![step_at("a", 0, 0), step_at("b", 1, 1), step_at("c", 2, 2)];
+        let new = vec![
+            step_at("a", 0, 0),
+            step_at("inserted", 5, 5),
+            step_at("b", 1, 1),
+            step_at("c", 2, 2),
+        ];
+
+        let mapping = StepMapping::between(&old, &new);
+
+        assert!(!mapping.is_identity());
+        assert_eq!(mapping.get(1).map(|m| m.index), Some(2));
+        assert_eq!(mapping.get(2).map(|m| m.index), Some(3));
+
+        assert_eq!(
+            mapping.changes(),
+            vec![
+                StepChange::Unchanged { from: 0, to: 0 },
+                StepChange::Added { to: 1 },
+                StepChange::Unchanged { from: 1, to: 2 },
+                StepChange::Unchanged { from: 2, to: 3 },
+            ]
+        );
+
+        let mapping = StepMapping::between(&new, &old);
+
+        assert_eq!(mapping.unmapped(), vec![1]);
+        assert_eq!(mapping.get_or_previous(1), 0);
+    }
+
+    #[test]
+    fn step_mapping_matches_modified_step_by_coordinates() {
+        let old = vec![step_at("a", 0, 0), step_at("b", 1, 1)];
+        let new = vec![step_at("a", 0, 0), step_at("b edited", 1, 1)];
+
+        let mapping = StepMapping::between(&old, &new);
+
+        assert_eq!(mapping.get(1).map(|m| (m.index, m.exact)), Some((1, false)));
+    }
+
+    #[test]
+    fn remap_progress_moves_current_step_and_checkboxes() {
+        let task = |label: &str| {
+            format!(
+                "<ul data-type='taskList'><li data-type='taskItem'><label><input type='checkbox'></label><div><p>{label}</p></div></li></ul>"
+            )
+        };
+        let old = vec![
+            step_at("a", 0, 0),
+            step_at(&format!("{}{}", task("one"), task("two")), 1, 1),
+        ];
+        let new = vec![
+            step_at("inserted", 9, 9),
+            step_at("a", 0, 0),
+            step_at(&format!("{}{}", task("zero"), task("two")), 1, 1),
+        ];
+
+        let mut progress = Progress {
+            id: 1,
+            current_step: 1,
+            steps: HashMap::from([(
+                1,
+                ConfStep {
+                    checkboxes: vec![1],
+                },
+            )]),
+            updated_at: None,
+        };
+
+        remap_progress(&mut progress, &StepMapping::between(&old, &new), &old, &new);
+
+        assert_eq!(progress.current_step, 2);
+        assert_eq!(
+            progress.steps.get(&2).map(|s| s.checkboxes.clone()),
+            Some(vec![1])
+        );
+    }
+}
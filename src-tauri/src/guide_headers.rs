@@ -0,0 +1,386 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::UNIX_EPOCH,
+};
+
+use log::{debug, warn};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    guide_quarantine::recover_quarantined_guides_in_background,
+    guides::{
+        parse_guide_or_quarantine, resolve_folder, Error, Folder, GameType, GuideLang,
+        GuideWithSteps, Status,
+    },
+    storage::ReadError,
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Enums
+
+#[derive(Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum GuideHeaderOrFolder {
+    Guide(GuideHeader),
+    Folder(Folder),
+}
+
+// Structs
+
+/// What listing views need from a guide, without its steps.
+#[taurpc::ipc_type]
+pub struct GuideHeader {
+    pub id: u32,
+    pub name: String,
+    pub status: Status,
+    pub lang: GuideLang,
+    #[serde(default)]
+    pub game_type: GameType,
+    pub order: u32,
+    pub updated_at: Option<String>,
+    pub node_image: Option<String>,
+    pub steps_count: u32,
+    #[serde(skip_deserializing, serialize_with = "crate::json::serialize_path")]
+    pub folder: Option<PathBuf>,
+}
+
+/// Fields of a guide file read to build its header, the steps are skipped and only counted.
+#[derive(Deserialize)]
+struct GuideFileHeader {
+    id: u32,
+    name: String,
+    status: Status,
+    lang: GuideLang,
+    #[serde(default)]
+    game_type: GameType,
+    order: u32,
+    updated_at: Option<String>,
+    node_image: Option<String>,
+    #[serde(deserialize_with = "count_elements")]
+    steps: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedHeader {
+    /// Modification time of the file in milliseconds, with its size to tell whether it changed.
+    modified: Option<u128>,
+    size: u64,
+    header: GuideHeader,
+}
+
+/// Headers of the guide files by path relative to the guides directory, stored in a sidecar
+/// file outside of the guides directory so it is not taken for a guide.
+#[derive(Default, Serialize, Deserialize)]
+struct HeadersIndex {
+    files: HashMap<String, IndexedHeader>,
+}
+
+/// Sidecar index of the guide headers, loaded on first use. A header is read again from its
+/// file when the file modification time or size changed.
+#[derive(Default)]
+pub struct GuideHeadersIndex {
+    index: Mutex<Option<HeadersIndex>>,
+}
+
+struct StepsCounter;
+
+// Implementations
+
+impl From<GuideFileHeader> for GuideHeader {
+    fn from(header: GuideFileHeader) -> Self {
+        GuideHeader {
+            id: header.id,
+            name: header.name,
+            status: header.status,
+            lang: header.lang,
+            game_type: header.game_type,
+            order: header.order,
+            updated_at: header.updated_at,
+            node_image: header.node_image,
+            steps_count: header.steps,
+            folder: None,
+        }
+    }
+}
+
+impl From<&GuideWithSteps> for GuideHeader {
+    fn from(guide: &GuideWithSteps) -> Self {
+        GuideHeader {
+            id: guide.id,
+            name: guide.name.clone(),
+            status: guide.status.clone(),
+            lang: guide.lang.clone(),
+            game_type: guide.game_type.clone(),
+            order: guide.order,
+            updated_at: guide.updated_at.clone(),
+            node_image: guide.node_image.clone(),
+            steps_count: guide.steps.len() as u32,
+            folder: guide.folder.clone(),
+        }
+    }
+}
+
+impl<'de> de::Visitor<'de> for StepsCounter {
+    type Value = u32;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of steps")
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<u32, A::Error> {
+        let mut count = 0;
+
+        while seq.next_element::<de::IgnoredAny>()?.is_some() {
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+impl GuideHeadersIndex {
+    fn with_index<T>(&self, file: &Path, f: impl FnOnce(&mut HeadersIndex) -> T) -> T {
+        let mut lock = self.index.lock().unwrap();
+        let index = lock.get_or_insert_with(|| read_index(file));
+
+        f(index)
+    }
+
+    /// Paths of the files where a guide was listed, relative to the guides directory.
+    fn guide_files(&self, file: &Path, guide_id: u32) -> Vec<String> {
+        self.with_index(file, |index| {
+            index
+                .files
+                .iter()
+                .filter(|(_, indexed)| indexed.header.id == guide_id)
+                .map(|(path, _)| path.clone())
+                .collect()
+        })
+    }
+}
+
+// Public Functions
+
+/// Guide headers and folders directly in `folder`, without parsing the steps of the guides
+/// whose file did not change since they were last listed.
+pub fn get_guide_headers_or_folders<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    folder: Option<String>,
+) -> Result<Vec<GuideHeaderOrFolder>, Error> {
+    let guides_dir = app_handle.path().app_guides_dir();
+    let dir = resolve_folder(&guides_dir, &folder.unwrap_or_default())?;
+    let index_file = app_handle.path().app_guide_headers_file();
+    let headers_index = app_handle.state::<GuideHeadersIndex>();
+    let quarantine_dir = app_handle.path().app_guides_quarantine_dir();
+
+    let mut result = vec![];
+    let mut quarantined = vec![];
+    let mut listed = vec![];
+    let mut changed = false;
+
+    for entry in fs::read_dir(&dir).map_err(|err| Error::ReadGuidesDir(err.to_string()))? {
+        let entry = entry.map_err(|err| Error::ReadGuidesDir(err.to_string()))?;
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if path.is_dir() {
+            result.push(GuideHeaderOrFolder::Folder(Folder { name: file_name }));
+            continue;
+        }
+
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let key = relative_key(&guides_dir, &path);
+        let modified = modified_millis(&metadata);
+
+        listed.push(key.clone());
+
+        let cached = headers_index.with_index(&index_file, |index| {
+            index
+                .files
+                .get(&key)
+                .filter(|indexed| indexed.modified == modified && indexed.size == metadata.len())
+                .map(|indexed| indexed.header.clone())
+        });
+
+        let header = match cached {
+            Some(header) => header,
+            None => {
+                let header = match read_header(&path) {
+                    Some(header) => header,
+                    // the full parse recovers the file from its backup or quarantines it
+                    None => {
+                        match parse_guide_or_quarantine(&path, &quarantine_dir, &mut quarantined)? {
+                            Some(guide) => GuideHeader::from(&guide),
+                            None => continue,
+                        }
+                    }
+                };
+
+                debug!("[GuideHeaders] indexed {:?}", path);
+
+                headers_index.with_index(&index_file, |index| {
+                    index.files.insert(
+                        key,
+                        IndexedHeader {
+                            modified,
+                            size: metadata.len(),
+                            header: header.clone(),
+                        },
+                    )
+                });
+                changed = true;
+
+                header
+            }
+        };
+
+        result.push(GuideHeaderOrFolder::Guide(GuideHeader {
+            folder: Some(dir.clone()),
+            ..header
+        }));
+    }
+
+    // forget the files of this folder which are gone
+    let dir_key = relative_key(&guides_dir, &dir);
+
+    headers_index.with_index(&index_file, |index| {
+        let before = index.files.len();
+
+        index.files.retain(|key, _| {
+            Path::new(key).parent().map(relative_dir_key) != Some(dir_key.clone())
+                || listed.contains(key)
+        });
+
+        changed |= index.files.len() != before;
+
+        if changed {
+            if let Err(err) = write_index(&index_file, index) {
+                warn!("[GuideHeaders] cannot save the headers index: {}", err);
+            }
+        }
+    });
+
+    recover_quarantined_guides_in_background(app_handle, quarantined);
+
+    Ok(result)
+}
+
+/// Parse the file of a guide listed before, `None` when it is not known or has moved.
+pub fn read_listed_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Option<GuideWithSteps> {
+    let guides_dir = app_handle.path().app_guides_dir();
+    let index_file = app_handle.path().app_guide_headers_file();
+    let keys = app_handle
+        .state::<GuideHeadersIndex>()
+        .guide_files(&index_file, guide_id);
+
+    keys.into_iter().find_map(|key| {
+        let path = guides_dir.join(key);
+
+        match crate::storage::read_json::<GuideWithSteps>(&path) {
+            Ok(Some(mut guide)) if guide.id == guide_id => {
+                guide.folder = path.parent().map(Path::to_path_buf);
+
+                Some(guide)
+            }
+            _ => None,
+        }
+    })
+}
+
+// Private Functions
+
+fn count_elements<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    deserializer.deserialize_seq(StepsCounter)
+}
+
+fn read_header(path: &Path) -> Option<GuideHeader> {
+    let text = fs::read_to_string(path).ok()?;
+
+    crate::json::from_str::<GuideFileHeader>(&text)
+        .ok()
+        .map(GuideHeader::from)
+}
+
+fn modified_millis(metadata: &fs::Metadata) -> Option<u128> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis())
+}
+
+/// Path relative to the guides directory with `/` separators, stable across platforms.
+fn relative_key(guides_dir: &Path, path: &Path) -> String {
+    relative_dir_key(path.strip_prefix(guides_dir).unwrap_or(path))
+}
+
+fn relative_dir_key(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn read_index(file: &Path) -> HeadersIndex {
+    match crate::storage::read_json::<HeadersIndex>(file) {
+        Ok(index) => index.unwrap_or_default(),
+        Err(ReadError::Io(err)) => {
+            warn!("[GuideHeaders] cannot read the headers index: {}", err);
+            HeadersIndex::default()
+        }
+        Err(ReadError::Malformed(err)) => {
+            warn!("[GuideHeaders] malformed headers index: {}", err);
+            HeadersIndex::default()
+        }
+    }
+}
+
+fn write_index(file: &Path, index: &HeadersIndex) -> Result<(), Error> {
+    let json = crate::json::serialize_pretty(index).map_err(Error::SerializeGuide)?;
+
+    crate::storage::write(file, json).map_err(|err| Error::WriteGuideFile(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{read_header, relative_key};
+
+    #[test]
+    fn read_header_counts_steps_without_keeping_them() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("42.json");
+
+        std::fs::write(
+            &path,
+            r#"{"id":42,"name":"Guide","status":"public","likes":0,"dislikes":0,"lang":"fr","order":3,"user":{"id":1,"name":"u","is_admin":0,"is_certified":0},"steps":[{"name":null,"map":null,"pos_x":0,"pos_y":0,"web_text":"<p>a</p>"},{"name":null,"map":null,"pos_x":1,"pos_y":1,"web_text":"<p>b</p>"}]}"#,
+        )
+        .unwrap();
+
+        let header = read_header(&path).unwrap();
+
+        assert_eq!(header.id, 42);
+        assert_eq!(header.order, 3);
+        assert_eq!(header.steps_count, 2);
+        assert_eq!(
+            relative_key(dir.path(), &dir.path().join("a").join("1.json")),
+            "a/1.json"
+        );
+        assert_eq!(relative_key(dir.path(), dir.path()), "");
+        assert!(read_header(Path::new("/missing.json")).is_none());
+    }
+}
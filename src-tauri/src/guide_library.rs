@@ -0,0 +1,299 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use log::{debug, error, info, warn};
+use notify::{EventKind, RecursiveMode, Watcher};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    guides::{GuideWithSteps, GuidesEventTrigger},
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Constants
+
+/// Delay during which the events following a change are gathered, a guide written by another
+/// program usually triggers several of them.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+/// How long the events on a path changed by the app are ignored by the watcher.
+const APP_CHANGE_GRACE: Duration = Duration::from_secs(2);
+
+// Structs
+
+/// In-memory copy of every downloaded guide, loaded on first use and kept up to date by the code
+/// paths writing, moving or deleting guides. Changes made to the files by other programs are
+/// detected by [`watch_guides_dir`].
+#[derive(Default)]
+pub struct GuidesLibrary {
+    guides: Mutex<Option<Vec<GuideWithSteps>>>,
+    /// Bumped on every change of the guides, the indexes built from them are outdated once it
+    /// moved.
+    generation: AtomicU64,
+    /// Paths under the guides directory the app is changing, with when, so the watcher only
+    /// reacts to the changes of other programs.
+    app_changes: Mutex<Vec<(PathBuf, Instant)>>,
+}
+
+/// Data extracted from every guide of the [`GuidesLibrary`], built on first use and built again
+/// on the first use after the library changed.
+pub struct LibraryIndex<T> {
+    index: Mutex<Option<(u64, HashMap<u32, T>)>>,
+}
+
+// Implementations
+
+impl GuidesLibrary {
+    pub fn is_loaded(&self) -> bool {
+        self.guides.lock().unwrap().is_some()
+    }
+
+    pub fn guide(&self, guide_id: u32) -> Option<GuideWithSteps> {
+        self.guides
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|guides| guides.iter().find(|guide| guide.id == guide_id).cloned())
+    }
+
+    pub fn rebuild(&self, guides: Vec<GuideWithSteps>) {
+        debug!("[GuidesLibrary] loaded {} guides", guides.len());
+
+        *self.guides.lock().unwrap() = Some(guides);
+        self.bump_generation();
+    }
+
+    /// Drop the library, it is loaded again from the disk on the next read.
+    pub fn invalidate(&self) {
+        *self.guides.lock().unwrap() = None;
+        self.bump_generation();
+    }
+
+    /// Guides stored in `folder` or one of its subfolders, `None` until the library is loaded.
+    pub fn guides_in(&self, folder: &Path) -> Option<Vec<GuideWithSteps>> {
+        self.guides.lock().unwrap().as_ref().map(|guides| {
+            guides
+                .iter()
+                .filter(|guide| {
+                    guide
+                        .folder
+                        .as_ref()
+                        .is_some_and(|guide_folder| guide_folder.starts_with(folder))
+                })
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Add or replace a guide. Does nothing until the library has been loaded once.
+    pub fn upsert(&self, guide: &GuideWithSteps) {
+        if let Some(guides) = self.guides.lock().unwrap().as_mut() {
+            match guides.iter().position(|g| g.id == guide.id) {
+                Some(index) => guides[index] = guide.clone(),
+                None => guides.push(guide.clone()),
+            }
+        }
+
+        self.bump_generation();
+    }
+
+    pub fn remove_guide(&self, guide_id: u32) {
+        if let Some(guides) = self.guides.lock().unwrap().as_mut() {
+            guides.retain(|guide| guide.id != guide_id);
+        }
+
+        self.bump_generation();
+    }
+
+    /// Remove every guide stored in `folder` or one of its subfolders.
+    pub fn remove_folder(&self, folder: &Path) {
+        if let Some(guides) = self.guides.lock().unwrap().as_mut() {
+            guides.retain(|guide| {
+                !guide
+                    .folder
+                    .as_ref()
+                    .is_some_and(|guide_folder| guide_folder.starts_with(folder))
+            });
+        }
+
+        self.bump_generation();
+    }
+
+    /// Tell the watcher the app is about to change `path`, a file or a folder of the guides
+    /// directory, so the events it triggers are not taken for an external change.
+    pub fn record_app_change(&self, path: &Path) {
+        let mut app_changes = self.app_changes.lock().unwrap();
+
+        app_changes.retain(|(_, at)| at.elapsed() < APP_CHANGE_GRACE);
+        app_changes.push((path.to_path_buf(), Instant::now()));
+    }
+
+    fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn is_app_change(&self, path: &Path) -> bool {
+        self.app_changes
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|(changed, at)| at.elapsed() < APP_CHANGE_GRACE && path.starts_with(changed))
+    }
+
+    /// Whether an event of the watcher comes from another program, the temporary and backup
+    /// files of the stores are ignored.
+    fn is_external_change(&self, event: &notify::Event) -> bool {
+        if matches!(event.kind, EventKind::Access(_)) {
+            return false;
+        }
+
+        event.paths.iter().any(|path| {
+            let is_store_file = path
+                .extension()
+                .is_some_and(|ext| ext == "tmp" || ext == "bak");
+
+            !is_store_file && !self.is_app_change(path)
+        })
+    }
+}
+
+impl<T> Default for LibraryIndex<T> {
+    fn default() -> Self {
+        LibraryIndex {
+            index: Mutex::new(None),
+        }
+    }
+}
+
+impl<T> LibraryIndex<T> {
+    /// Run `f` on the index, built first with `build` when the library changed since. `None`
+    /// until the library is loaded.
+    pub fn with_index<U>(
+        &self,
+        library: &GuidesLibrary,
+        build: impl Fn(&GuideWithSteps) -> T,
+        f: impl FnOnce(&HashMap<u32, T>) -> U,
+    ) -> Option<U> {
+        let mut index = self.index.lock().unwrap();
+        let generation = library.generation.load(Ordering::SeqCst);
+
+        if !matches!(&*index, Some((built, _)) if *built == generation) {
+            let guides = library.guides.lock().unwrap();
+            let guides = guides.as_ref()?;
+
+            *index = Some((
+                generation,
+                guides
+                    .iter()
+                    .map(|guide| (guide.id, build(guide)))
+                    .collect(),
+            ));
+        }
+
+        index.as_ref().map(|(_, index)| f(index))
+    }
+}
+
+// Public Functions
+
+/// Watch the guides directory in the background. When other programs change its files, the
+/// library is dropped and `guidesChanged` is emitted.
+pub fn watch_guides_dir<R: Runtime>(app_handle: AppHandle<R>) {
+    let guides_dir = app_handle.path().app_guides_dir();
+
+    if let Err(err) = std::fs::create_dir_all(&guides_dir) {
+        warn!("[GuidesLibrary] cannot create {:?}: {}", guides_dir, err);
+    }
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let _ = sender.send(event);
+    })
+    .and_then(|mut watcher| {
+        watcher.watch(&guides_dir, RecursiveMode::Recursive)?;
+
+        Ok(watcher)
+    });
+
+    let watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            error!("[GuidesLibrary] cannot watch the guides directory: {}", err);
+            return;
+        }
+    };
+
+    info!(
+        "[GuidesLibrary] watching {:?}",
+        app_handle.path().app_guides_dir()
+    );
+
+    tauri::async_runtime::spawn(async move {
+        // dropping the watcher stops it
+        let _watcher = watcher;
+        let library = app_handle.state::<GuidesLibrary>();
+
+        while let Some(event) = receiver.recv().await {
+            let event = match event {
+                Ok(event) if library.is_external_change(&event) => event,
+                Ok(_) => continue,
+                Err(err) => {
+                    warn!("[GuidesLibrary] watch error: {}", err);
+                    continue;
+                }
+            };
+
+            // the other events of the change are covered by this one
+            tokio::time::sleep(WATCH_DEBOUNCE).await;
+
+            while receiver.try_recv().is_ok() {}
+
+            info!(
+                "[GuidesLibrary] guides changed outside of the app: {:?}",
+                event.paths
+            );
+
+            library.invalidate();
+
+            if let Err(err) = GuidesEventTrigger::new(app_handle.clone()).guides_changed() {
+                warn!(
+                    "[GuidesLibrary] failed to emit guides_changed event: {:?}",
+                    err
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use notify::{event::CreateKind, Event, EventKind};
+
+    use super::GuidesLibrary;
+
+    fn create(path: &str) -> Event {
+        Event::new(EventKind::Create(CreateKind::File)).add_path(Path::new(path).to_path_buf())
+    }
+
+    #[test]
+    fn is_external_change_ignores_paths_changed_by_the_app() {
+        let library = GuidesLibrary::default();
+
+        library.record_app_change(Path::new("/guides/folder"));
+        library.record_app_change(Path::new("/guides/1.json"));
+
+        assert!(!library.is_external_change(&create("/guides/folder/2.json")));
+        assert!(!library.is_external_change(&create("/guides/1.json")));
+        assert!(!library.is_external_change(&create("/guides/3.json.tmp")));
+        assert!(library.is_external_change(&create("/guides/3.json")));
+        assert!(library.is_external_change(&create("/guides/other/1.json")));
+    }
+}
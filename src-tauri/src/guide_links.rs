@@ -0,0 +1,279 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use log::{debug, info, warn};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+
+use crate::{
+    guide_library::GuidesLibrary,
+    guides::{
+        get_guides_from_handle, get_guides_from_server, resolve_folder, write_guides, Error,
+        GuideWithSteps, Guides,
+    },
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Constants
+
+/// Bound on the rounds of a recursive download, each round fetches the guides linked by the
+/// guides downloaded in the previous one.
+const MAX_DOWNLOAD_ROUNDS: usize = 5;
+
+// Structs
+
+/// A `guide-step` node of a step pointing to another guide.
+#[derive(Debug, PartialEq)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideLink {
+    pub from_guide_id: u32,
+    pub from_step_index: u32,
+    pub to_guide_id: u32,
+    /// Step number of the linked guide as written in the link, starting at 1.
+    pub to_step_number: Option<u32>,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideDependencies {
+    pub links: Vec<GuideLink>,
+    /// Linked guides which are not downloaded, sorted by id.
+    pub missing_guide_ids: Vec<u32>,
+}
+
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedGuidesDownload {
+    pub downloaded: Vec<u32>,
+    pub failed: HashMap<u32, String>,
+}
+
+// Public Functions
+
+/// Links of every step of a guide to other guides.
+pub fn extract_guide_links(guide: &GuideWithSteps) -> Vec<GuideLink> {
+    let selector = scraper::Selector::parse("[data-type='guide-step']").unwrap();
+    let mut links = vec![];
+
+    for (step_index, step) in guide.steps.iter().enumerate() {
+        if !step.web_text.contains("guide-step") {
+            continue;
+        }
+
+        let document = scraper::Html::parse_fragment(&step.web_text);
+
+        for element in document.select(&selector) {
+            let Some(to_guide_id) = element
+                .value()
+                .attr("guideid")
+                .and_then(|id| id.trim().parse::<u32>().ok())
+            else {
+                continue;
+            };
+
+            // the editor writes 0 for links to the same guide
+            if to_guide_id == 0 || to_guide_id == guide.id {
+                continue;
+            }
+
+            links.push(GuideLink {
+                from_guide_id: guide.id,
+                from_step_index: step_index as u32,
+                to_guide_id,
+                to_step_number: element
+                    .value()
+                    .attr("stepnumber")
+                    .and_then(|number| number.trim().parse::<u32>().ok()),
+            });
+        }
+    }
+
+    links
+}
+
+/// Cross-guide links of the downloaded guides, or only of `guide_id` and the downloaded guides
+/// it leads to.
+pub fn get_guide_dependencies<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: Option<u32>,
+) -> Result<GuideDependencies, Error> {
+    let guides = get_guides_from_handle(app_handle, "".to_string())?.guides;
+
+    Ok(build_dependencies(&guides, guide_id))
+}
+
+/// Download the guides linked by the downloaded ones (or by `guide_id`) which are missing, into
+/// `folder`. With `recursive`, the guides they link to are downloaded too.
+pub async fn download_linked_guides<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: Option<u32>,
+    folder: String,
+    recursive: bool,
+) -> Result<LinkedGuidesDownload, Error> {
+    let folder = resolve_folder(&app_handle.path().app_guides_dir(), &folder)?;
+    let http_client = app_handle.state::<reqwest::Client>();
+    let mut result = LinkedGuidesDownload::default();
+
+    for round in 0..MAX_DOWNLOAD_ROUNDS {
+        let guides = get_guides_from_handle(app_handle, "".to_string())?.guides;
+        let missing = build_dependencies(&guides, guide_id)
+            .missing_guide_ids
+            .into_iter()
+            .filter(|id| !result.failed.contains_key(id))
+            .collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            break;
+        }
+
+        info!(
+            "[GuideLinks] downloading linked guides {:?} (round {})",
+            missing, round
+        );
+
+        app_handle
+            .state::<GuidesLibrary>()
+            .record_app_change(&folder);
+        std::fs::create_dir_all(&folder).map_err(|err| Error::CreateGuidesDir(err.to_string()))?;
+
+        let results = get_guides_from_server(missing.clone(), &http_client, |_, _| {}).await;
+        let mut downloaded = vec![];
+
+        for (id, guide) in missing.into_iter().zip(results) {
+            match guide {
+                Ok(mut guide) => {
+                    debug!("[GuideLinks] downloaded linked guide {}", id);
+
+                    guide.folder = Some(folder.clone());
+                    downloaded.push(guide);
+                    result.downloaded.push(id);
+                }
+                Err(err) => {
+                    warn!("[GuideLinks] cannot download linked guide {}: {}", id, err);
+
+                    result.failed.insert(id, err.to_string());
+                }
+            }
+        }
+
+        write_guides(&Guides { guides: downloaded }, app_handle)?;
+
+        if !recursive {
+            break;
+        }
+    }
+
+    Ok(result)
+}
+
+// Private Functions
+
+fn build_dependencies(guides: &[GuideWithSteps], guide_id: Option<u32>) -> GuideDependencies {
+    let installed = guides.iter().map(|g| g.id).collect::<HashSet<_>>();
+
+    let links = match guide_id {
+        None => guides.iter().flat_map(extract_guide_links).collect(),
+        Some(guide_id) => {
+            // follow the links from the guide through the downloaded guides only
+            let mut links = vec![];
+            let mut visited = HashSet::from([guide_id]);
+            let mut queue = vec![guide_id];
+
+            while let Some(id) = queue.pop() {
+                let Some(guide) = guides.iter().find(|g| g.id == id) else {
+                    continue;
+                };
+
+                for link in extract_guide_links(guide) {
+                    if visited.insert(link.to_guide_id) {
+                        queue.push(link.to_guide_id);
+                    }
+
+                    links.push(link);
+                }
+            }
+
+            links
+        }
+    };
+
+    let missing_guide_ids = links
+        .iter()
+        .map(|link: &GuideLink| link.to_guide_id)
+        .filter(|id| !installed.contains(id))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    GuideDependencies {
+        links,
+        missing_guide_ids,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_dependencies, extract_guide_links, GuideLink};
+    use crate::test_guides::{guide, step};
+
+    #[test]
+    fn extract_guide_links_reads_guide_step_nodes() {
+        let guide = guide(1)
+            .steps(vec![
+                step("<p>no link</p>"),
+                step("<p><span data-type='guide-step' guideid='42' stepnumber='3'>go</span> and <span data-type='guide-step' guideid='1' stepnumber='2'>back</span> <span data-type='guide-step' guideid='0' stepnumber='4'>here</span></p>"),
+                step("<span data-type=\"guide-step\" guideid=\"7\">other</span>"),
+            ])
+            .build();
+
+        assert_eq!(
+            extract_guide_links(&guide),
+            vec![
+                GuideLink {
+                    from_guide_id: 1,
+                    from_step_index: 1,
+                    to_guide_id: 42,
+                    to_step_number: Some(3),
+                },
+                GuideLink {
+                    from_guide_id: 1,
+                    from_step_index: 2,
+                    to_guide_id: 7,
+                    to_step_number: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn build_dependencies_follows_downloaded_guides_only() {
+        let guides = vec![
+            guide(1)
+                .steps(vec![step(
+                    "<span data-type='guide-step' guideid='2'></span>",
+                )])
+                .build(),
+            guide(2)
+                .steps(vec![step(
+                    "<span data-type='guide-step' guideid='3'></span>",
+                )])
+                .build(),
+            guide(4)
+                .steps(vec![step(
+                    "<span data-type='guide-step' guideid='5'></span>",
+                )])
+                .build(),
+        ];
+
+        assert_eq!(
+            build_dependencies(&guides, Some(1)).missing_guide_ids,
+            vec![3]
+        );
+        assert_eq!(
+            build_dependencies(&guides, None).missing_guide_ids,
+            vec![3, 5]
+        );
+    }
+}
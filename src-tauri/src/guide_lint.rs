@@ -0,0 +1,402 @@
+use std::collections::HashMap;
+
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+
+use crate::guides::{get_guides_from_handle, get_installed_guide, Error, GuideWithSteps};
+
+// Constants
+
+/// Coordinates of the maps of the game all fit in this bound, on both axes.
+const MAX_MAP_COORDINATE: i32 = 200;
+const IMAGE_CHECK_TIMEOUT_SECS: u64 = 10;
+const QUEST_STATUSES: [&str; 4] = ["setup", "start", "in_progress", "end"];
+
+// Enums
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LintSeverity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LintIssueKind {
+    /// The step has no map, its position is not displayed.
+    MissingPosition,
+    ImplausiblePosition,
+    EmptyStep,
+    /// A `guide-step` link without guide id, or pointing to a step the guide does not have.
+    BrokenGuideStepLink,
+    /// A `guide-step` link to a guide which is not downloaded, its step cannot be checked.
+    UncheckedGuideStepLink,
+    BrokenImageUrl,
+    UnreachableImage,
+    /// A link to a domain outside of the allowed ones, it is displayed as plain text.
+    LinkNotAllowed,
+    UnknownQuestStatus,
+    MissingQuestName,
+}
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct LintIssue {
+    pub kind: LintIssueKind,
+    pub severity: LintSeverity,
+    /// Offending value, like the url of an image or the status of a quest block.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct StepLintReport {
+    pub step_index: u32,
+    pub issues: Vec<LintIssue>,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideLintReport {
+    pub guide_id: u32,
+    /// Only the steps with at least one issue, in order.
+    pub steps: Vec<StepLintReport>,
+    pub errors: u32,
+    pub warnings: u32,
+}
+
+// Implementations
+
+impl LintIssueKind {
+    fn severity(&self) -> LintSeverity {
+        match self {
+            LintIssueKind::MissingPosition
+            | LintIssueKind::UncheckedGuideStepLink
+            | LintIssueKind::LinkNotAllowed => LintSeverity::Warning,
+            LintIssueKind::ImplausiblePosition
+            | LintIssueKind::EmptyStep
+            | LintIssueKind::BrokenGuideStepLink
+            | LintIssueKind::BrokenImageUrl
+            | LintIssueKind::UnreachableImage
+            | LintIssueKind::UnknownQuestStatus
+            | LintIssueKind::MissingQuestName => LintSeverity::Error,
+        }
+    }
+}
+
+impl LintIssue {
+    fn new(kind: LintIssueKind, detail: Option<String>) -> Self {
+        LintIssue {
+            severity: kind.severity(),
+            kind,
+            detail,
+        }
+    }
+}
+
+// Public Functions
+
+/// Check a downloaded guide for structural problems. With `check_images`, every image is
+/// requested to find the unreachable ones.
+pub async fn lint_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    check_images: bool,
+) -> Result<GuideLintReport, Error> {
+    info!("[GuideLint] linting guide {}", guide_id);
+
+    let guide = get_installed_guide(app_handle, guide_id)?;
+    let steps_count_by_guide = get_guides_from_handle(app_handle, "".to_string())?
+        .guides
+        .iter()
+        .map(|guide| (guide.id, guide.steps.len() as u32))
+        .collect::<HashMap<_, _>>();
+
+    let mut steps = lint_steps(&guide, &steps_count_by_guide);
+
+    if check_images {
+        let http_client = app_handle.state::<reqwest::Client>();
+
+        for (step_index, step) in guide.steps.iter().enumerate() {
+            for url in crate::image_cache::image_urls(&step.web_text) {
+                if tauri::Url::parse(&url).is_err() {
+                    continue;
+                }
+
+                if let Some(error) = check_image(&http_client, &url).await {
+                    debug!("[GuideLint] unreachable image {}: {}", url, error);
+
+                    push_issue(
+                        &mut steps,
+                        step_index as u32,
+                        LintIssue::new(LintIssueKind::UnreachableImage, Some(url)),
+                    );
+                }
+            }
+        }
+    }
+
+    let count = |severity: LintSeverity| {
+        steps
+            .iter()
+            .flat_map(|step| &step.issues)
+            .filter(|issue| issue.severity == severity)
+            .count() as u32
+    };
+
+    Ok(GuideLintReport {
+        guide_id,
+        errors: count(LintSeverity::Error),
+        warnings: count(LintSeverity::Warning),
+        steps,
+    })
+}
+
+// Private Functions
+
+fn lint_steps(
+    guide: &GuideWithSteps,
+    steps_count_by_guide: &HashMap<u32, u32>,
+) -> Vec<StepLintReport> {
+    let guide_step_selector = scraper::Selector::parse("[data-type='guide-step']").unwrap();
+    let quest_selector = scraper::Selector::parse("[data-type='quest-block']").unwrap();
+    let link_selector = scraper::Selector::parse("a[href]").unwrap();
+    let content_selector = scraper::Selector::parse("img, [data-type]").unwrap();
+
+    let mut steps = vec![];
+
+    for (step_index, step) in guide.steps.iter().enumerate() {
+        let document = scraper::Html::parse_fragment(&step.web_text);
+        let mut issues = vec![];
+
+        match step.map.as_deref().map(str::trim) {
+            None | Some("") => issues.push(LintIssue::new(LintIssueKind::MissingPosition, None)),
+            Some(map) if map.eq_ignore_ascii_case("nomap") => {}
+            Some(_) => {
+                if step.pos_x.abs() > MAX_MAP_COORDINATE || step.pos_y.abs() > MAX_MAP_COORDINATE {
+                    issues.push(LintIssue::new(
+                        LintIssueKind::ImplausiblePosition,
+                        Some(format!("[{},{}]", step.pos_x, step.pos_y)),
+                    ));
+                }
+            }
+        }
+
+        if crate::search::html_to_plain_text(&step.web_text).is_empty()
+            && document.select(&content_selector).next().is_none()
+        {
+            issues.push(LintIssue::new(LintIssueKind::EmptyStep, None));
+        }
+
+        for element in document.select(&guide_step_selector) {
+            let attrs = element.value();
+            let step_number = attrs
+                .attr("stepnumber")
+                .and_then(|number| number.trim().parse::<u32>().ok());
+            let Some(linked_guide_id) = attrs
+                .attr("guideid")
+                .and_then(|id| id.trim().parse::<u32>().ok())
+            else {
+                issues.push(LintIssue::new(
+                    LintIssueKind::BrokenGuideStepLink,
+                    attrs.attr("guideid").map(str::to_string),
+                ));
+                continue;
+            };
+            // 0 links to the guide itself
+            let linked_guide_id = match linked_guide_id {
+                0 => guide.id,
+                id => id,
+            };
+            let detail = Some(format!(
+                "{}#{}",
+                linked_guide_id,
+                step_number.map_or("?".to_string(), |n| n.to_string())
+            ));
+
+            let steps_count = if linked_guide_id == guide.id {
+                Some(guide.steps.len() as u32)
+            } else {
+                steps_count_by_guide.get(&linked_guide_id).copied()
+            };
+
+            match (steps_count, step_number) {
+                (None, _) => issues.push(LintIssue::new(
+                    LintIssueKind::UncheckedGuideStepLink,
+                    detail,
+                )),
+                (Some(count), Some(number)) if number >= 1 && number <= count => {}
+                // no step number leads to the progress of the player in the guide
+                (Some(_), None) if attrs.attr("stepnumber").is_none() => {}
+                (Some(_), _) => {
+                    issues.push(LintIssue::new(LintIssueKind::BrokenGuideStepLink, detail))
+                }
+            }
+        }
+
+        for url in crate::image_cache::image_urls(&step.web_text) {
+            if !is_valid_image_url(&url) {
+                issues.push(LintIssue::new(LintIssueKind::BrokenImageUrl, Some(url)));
+            }
+        }
+
+        for element in document.select(&link_selector) {
+            let href = element.value().attr("href").unwrap_or_default().trim();
+            let is_http = href.starts_with("http://") || href.starts_with("https://");
+
+            if is_http && !crate::security::is_valid_link(href) {
+                issues.push(LintIssue::new(
+                    LintIssueKind::LinkNotAllowed,
+                    Some(href.to_string()),
+                ));
+            }
+        }
+
+        for element in document.select(&quest_selector) {
+            let attrs = element.value();
+
+            if attrs
+                .attr("questname")
+                .is_none_or(|name| name.trim().is_empty())
+            {
+                issues.push(LintIssue::new(LintIssueKind::MissingQuestName, None));
+            }
+
+            let status = attrs.attr("status").unwrap_or_default();
+
+            if !QUEST_STATUSES.contains(&status) {
+                issues.push(LintIssue::new(
+                    LintIssueKind::UnknownQuestStatus,
+                    Some(status.to_string()),
+                ));
+            }
+        }
+
+        if !issues.is_empty() {
+            steps.push(StepLintReport {
+                step_index: step_index as u32,
+                issues,
+            });
+        }
+    }
+
+    steps
+}
+
+fn is_valid_image_url(url: &str) -> bool {
+    if url.starts_with("data:image/") {
+        return true;
+    }
+
+    tauri::Url::parse(url).is_ok_and(|url| {
+        matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|h| !h.is_empty())
+    })
+}
+
+/// Request an image, returning why it cannot be fetched.
+async fn check_image(http_client: &reqwest::Client, url: &str) -> Option<String> {
+    let response = http_client
+        .head(url)
+        .timeout(std::time::Duration::from_secs(IMAGE_CHECK_TIMEOUT_SECS))
+        .send()
+        .await;
+
+    match response {
+        Ok(response) if response.status().is_success() => None,
+        Ok(response) => Some(response.status().to_string()),
+        Err(err) => Some(err.to_string()),
+    }
+}
+
+fn push_issue(steps: &mut Vec<StepLintReport>, step_index: u32, issue: LintIssue) {
+    match steps.iter().position(|step| step.step_index >= step_index) {
+        Some(position) if steps[position].step_index == step_index => {
+            steps[position].issues.push(issue)
+        }
+        Some(position) => steps.insert(
+            position,
+            StepLintReport {
+                step_index,
+                issues: vec![issue],
+            },
+        ),
+        None => steps.push(StepLintReport {
+            step_index,
+            issues: vec![issue],
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{lint_steps, LintIssueKind};
+    use crate::{
+        guides::GuideStep,
+        test_guides::{guide, step, step_at},
+    };
+
+    fn step_nomap(web_text: &str) -> GuideStep {
+        GuideStep {
+            map: Some("nomap".to_string()),
+            ..step(web_text)
+        }
+    }
+
+    #[test]
+    fn lint_steps_reports_issues_per_step() {
+        let guide = guide(1)
+            .steps(vec![
+                step_at("<p>fine</p>", 4, -18),
+                step("<p></p>"),
+                step_nomap("<span data-type='guide-step' guideid='0' stepnumber='9'>x</span><span data-type='guide-step' guideid='2' stepnumber='3'>y</span><span data-type='guide-step' guideid='3' stepnumber='1'>z</span>"),
+                step_at("<img src='not an url'><a href='https://evil.example/x'>x</a><a href='https://dofusdb.fr/a'>ok</a>", 999, 0),
+                step_nomap("<div data-type='quest-block' questname='Dopple' status='done'>q</div>"),
+            ])
+            .build();
+
+        let steps = lint_steps(&guide, &HashMap::from([(2, 5)]));
+        let kinds = |index: usize| {
+            steps[index]
+                .issues
+                .iter()
+                .map(|issue| issue.kind.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            steps.iter().map(|s| s.step_index).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            kinds(0),
+            vec![LintIssueKind::MissingPosition, LintIssueKind::EmptyStep]
+        );
+        assert_eq!(
+            kinds(1),
+            vec![
+                LintIssueKind::BrokenGuideStepLink,
+                LintIssueKind::UncheckedGuideStepLink
+            ]
+        );
+        assert_eq!(steps[1].issues[0].detail.as_deref(), Some("1#9"));
+        assert_eq!(
+            kinds(2),
+            vec![
+                LintIssueKind::ImplausiblePosition,
+                LintIssueKind::BrokenImageUrl,
+                LintIssueKind::LinkNotAllowed
+            ]
+        );
+        assert_eq!(kinds(3), vec![LintIssueKind::UnknownQuestStatus]);
+    }
+}
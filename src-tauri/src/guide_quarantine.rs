@@ -0,0 +1,422 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+
+use crate::{
+    guide_library::GuidesLibrary,
+    guides::{
+        get_guides_from_server, write_guides, Error, GuideWithSteps, Guides, GuidesEventTrigger,
+        RemovedGuideFile,
+    },
+    storage::ReadError,
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Constants
+
+const ENTRY_FILE: &str = "entry.json";
+
+// Structs
+
+/// A malformed guide file moved out of the guides directory.
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedGuideFile {
+    pub entry: String,
+    pub id: Option<u32>,
+    pub file_name: String,
+    /// Absolute path of the folder the file was stored in.
+    #[serde(skip_deserializing, serialize_with = "crate::json::serialize_path")]
+    pub folder: Option<PathBuf>,
+    pub quarantined_at: String,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct MalformedGuidesReport {
+    pub recovered: Vec<QuarantinedGuideFile>,
+    pub failed: Vec<QuarantinedGuideFile>,
+}
+
+/// Metadata stored next to a quarantined file to put it back where it was.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuarantineEntry {
+    id: Option<u32>,
+    file_name: String,
+    original_folder: PathBuf,
+    quarantined_at: String,
+}
+
+// Implementations
+
+impl QuarantineEntry {
+    fn into_file(self, entry: String) -> QuarantinedGuideFile {
+        QuarantinedGuideFile {
+            entry,
+            id: self.id,
+            file_name: self.file_name,
+            folder: Some(self.original_folder),
+            quarantined_at: self.quarantined_at,
+        }
+    }
+}
+
+// Public Functions
+
+/// Move a malformed guide file to `quarantine_dir/<entry>/`, along with the metadata needed to
+/// restore it into its original folder.
+pub fn quarantine_guide_file(
+    quarantine_dir: &Path,
+    file_path: &Path,
+) -> Result<QuarantinedGuideFile, Error> {
+    let file_name = file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let id = file_path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse::<u32>().ok());
+
+    let now = chrono::Utc::now();
+    let base_entry = format!(
+        "{}-{}",
+        now.timestamp_millis(),
+        id.map(|id| id.to_string())
+            .unwrap_or_else(|| file_name.replace('.', "_"))
+    );
+
+    let mut entry = base_entry.clone();
+    let mut suffix = 1;
+
+    while quarantine_dir.join(&entry).exists() {
+        entry = format!("{}-{}", base_entry, suffix);
+        suffix += 1;
+    }
+
+    let entry_dir = quarantine_dir.join(&entry);
+
+    debug!(
+        "[Quarantine] moving malformed guide file {:?} to {:?}",
+        file_path, entry_dir
+    );
+
+    fs::create_dir_all(&entry_dir).map_err(|err| Error::QuarantineGuideFile(err.to_string()))?;
+
+    let metadata = QuarantineEntry {
+        id,
+        file_name: file_name.clone(),
+        original_folder: file_path.parent().unwrap_or(Path::new("")).to_path_buf(),
+        quarantined_at: now.to_rfc3339(),
+    };
+    let json = crate::json::serialize_pretty(&metadata).map_err(Error::SerializeGuide)?;
+
+    crate::storage::write(&entry_dir.join(ENTRY_FILE), json)
+        .map_err(|err| Error::QuarantineGuideFile(err.to_string()))?;
+
+    // rename only works on the same filesystem, which is always the case under the config dir
+    fs::rename(file_path, entry_dir.join(&file_name))
+        .map_err(|err| Error::QuarantineGuideFile(err.to_string()))?;
+
+    // the backup was malformed too, otherwise the file would have been recovered from it
+    if let Err(err) = crate::storage::remove(&crate::storage::backup_path(file_path)) {
+        warn!(
+            "[Quarantine] failed to remove backup of {:?}: {}",
+            file_path, err
+        );
+    }
+
+    Ok(metadata.into_file(entry))
+}
+
+/// Quarantined guide files, the oldest first.
+pub fn list_quarantined_guides(quarantine_dir: &Path) -> Result<Vec<QuarantinedGuideFile>, Error> {
+    if !quarantine_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut entries = fs::read_dir(quarantine_dir)
+        .map_err(|err| Error::ReadQuarantine(err.to_string()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter_map(|entry| match read_entry(quarantine_dir, &entry) {
+            Ok(file) => Some(file),
+            Err(err) => {
+                warn!("[Quarantine] skipping entry {}: {}", entry, err);
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    entries.sort_by(|a, b| a.quarantined_at.cmp(&b.quarantined_at));
+
+    Ok(entries)
+}
+
+/// Re-download the quarantined guides into their original folder without blocking the caller,
+/// then report which ones could be recovered.
+pub fn recover_quarantined_guides_in_background<R: Runtime>(
+    app: &AppHandle<R>,
+    files: Vec<QuarantinedGuideFile>,
+) {
+    if files.is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let report = recover_quarantined_guides(&app, files).await;
+
+        emit_malformed_guides_report(&app, report);
+    });
+}
+
+/// Put a quarantined guide back into its original folder. The file is restored as is when it can
+/// now be parsed (e.g. fixed by hand), otherwise the guide is downloaded again from the server.
+pub async fn restore_quarantined_guide<R: Runtime>(
+    app: &AppHandle<R>,
+    entry: String,
+) -> Result<GuideWithSteps, Error> {
+    info!("[Quarantine] restoring entry {}", entry);
+
+    let quarantine_dir = app.path().app_guides_quarantine_dir();
+    let file = read_entry(&quarantine_dir, &entry)?;
+    let quarantined_path = quarantine_dir.join(&entry).join(&file.file_name);
+
+    let guide = match crate::storage::read_json::<GuideWithSteps>(&quarantined_path) {
+        Ok(Some(mut guide)) => {
+            guide.folder = file.folder.clone();
+            guide
+        }
+        _ => {
+            let id = file
+                .id
+                .ok_or_else(|| Error::QuarantinedGuideUnrecoverable(entry.clone()))?;
+            let http_client = app.state::<reqwest::Client>();
+            let mut guide = crate::guides::get_guide_from_server(id, &http_client).await?;
+
+            guide.folder = file.folder.clone();
+            guide
+        }
+    };
+
+    write_recovered_guide(app, &guide)?;
+    remove_entry(&quarantine_dir, &entry)?;
+
+    Ok(guide)
+}
+
+/// Permanently delete quarantined files. `None` purges the whole quarantine.
+pub fn purge_quarantined_guides<R: Runtime>(
+    app: &AppHandle<R>,
+    entries: Option<Vec<String>>,
+) -> Result<(), Error> {
+    let quarantine_dir = app.path().app_guides_quarantine_dir();
+
+    let entries = match entries {
+        Some(entries) => entries,
+        None => list_quarantined_guides(&quarantine_dir)?
+            .into_iter()
+            .map(|file| file.entry)
+            .collect(),
+    };
+
+    for entry in entries {
+        info!("[Quarantine] purging entry {}", entry);
+
+        remove_entry(&quarantine_dir, &entry)?;
+    }
+
+    Ok(())
+}
+
+// Private Functions
+
+async fn recover_quarantined_guides<R: Runtime>(
+    app: &AppHandle<R>,
+    files: Vec<QuarantinedGuideFile>,
+) -> MalformedGuidesReport {
+    let ids = files.iter().filter_map(|file| file.id).collect::<Vec<_>>();
+
+    info!("[Quarantine] re-downloading malformed guides {:?}", ids);
+
+    let results = if ids.is_empty() {
+        vec![]
+    } else {
+        let http_client = app.state::<reqwest::Client>();
+
+        get_guides_from_server(ids.clone(), &http_client, |_, _| {}).await
+    };
+
+    let mut downloaded = ids.into_iter().zip(results).collect::<Vec<_>>();
+    let quarantine_dir = app.path().app_guides_quarantine_dir();
+    let mut report = MalformedGuidesReport {
+        recovered: vec![],
+        failed: vec![],
+    };
+
+    for file in files {
+        let result = file
+            .id
+            .and_then(|id| {
+                let index = downloaded.iter().position(|(other, _)| *other == id)?;
+
+                Some(downloaded.remove(index).1)
+            })
+            .unwrap_or_else(|| Err(Error::QuarantinedGuideUnrecoverable(file.entry.clone())))
+            .and_then(|mut guide| {
+                guide.folder = file.folder.clone();
+
+                write_recovered_guide(app, &guide)
+            })
+            .and_then(|_| remove_entry(&quarantine_dir, &file.entry));
+
+        match result {
+            Ok(()) => report.recovered.push(file),
+            Err(err) => {
+                warn!(
+                    "[Quarantine] cannot recover guide file {}: {}",
+                    file.file_name, err
+                );
+
+                report.failed.push(file);
+            }
+        }
+    }
+
+    report
+}
+
+fn write_recovered_guide<R: Runtime>(
+    app: &AppHandle<R>,
+    guide: &GuideWithSteps,
+) -> Result<(), Error> {
+    if let Some(folder) = &guide.folder {
+        app.state::<GuidesLibrary>().record_app_change(folder);
+        fs::create_dir_all(folder).map_err(|err| Error::CreateGuidesDir(err.to_string()))?;
+    }
+
+    write_guides(
+        &Guides {
+            guides: vec![guide.clone()],
+        },
+        app,
+    )
+}
+
+fn emit_malformed_guides_report<R: Runtime>(app: &AppHandle<R>, report: MalformedGuidesReport) {
+    let trigger = GuidesEventTrigger::new(app.clone());
+
+    // kept for listeners only interested in the guides missing from the library
+    let removed = report
+        .failed
+        .iter()
+        .map(|file| RemovedGuideFile {
+            id: file.id,
+            file_name: file.file_name.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    if !removed.is_empty() {
+        if let Err(err) = trigger.malformed_guides_removed(removed) {
+            warn!(
+                "[Quarantine] failed to emit malformed_guides_removed event: {:?}",
+                err
+            );
+        }
+    }
+
+    if let Err(err) = trigger.malformed_guides_recovered(report) {
+        warn!(
+            "[Quarantine] failed to emit malformed_guides_recovered event: {:?}",
+            err
+        );
+    }
+}
+
+fn read_entry(quarantine_dir: &Path, entry: &str) -> Result<QuarantinedGuideFile, Error> {
+    if !is_valid_entry_name(entry) {
+        return Err(Error::QuarantinedGuideNotFound(entry.to_string()));
+    }
+
+    match crate::storage::read_json::<QuarantineEntry>(&quarantine_dir.join(entry).join(ENTRY_FILE))
+    {
+        Ok(Some(metadata)) => Ok(metadata.into_file(entry.to_string())),
+        Ok(None) => Err(Error::QuarantinedGuideNotFound(entry.to_string())),
+        Err(ReadError::Io(err)) => Err(Error::ReadQuarantine(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::ReadQuarantine(err.to_string())),
+    }
+}
+
+fn remove_entry(quarantine_dir: &Path, entry: &str) -> Result<(), Error> {
+    if !is_valid_entry_name(entry) {
+        return Err(Error::QuarantinedGuideNotFound(entry.to_string()));
+    }
+
+    match fs::remove_dir_all(quarantine_dir.join(entry)) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+            Err(Error::PurgeQuarantine(err.to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Entry names come from the frontend, they must not escape the quarantine directory.
+fn is_valid_entry_name(entry: &str) -> bool {
+    !entry.is_empty()
+        && entry
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{is_valid_entry_name, list_quarantined_guides, quarantine_guide_file};
+
+    #[test]
+    fn quarantine_guide_file_keeps_original_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let folder = dir.path().join("guides").join("dofus");
+        let quarantine_dir = dir.path().join("guides_quarantine");
+
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("2022.json"), "{").unwrap();
+
+        let file = quarantine_guide_file(&quarantine_dir, &folder.join("2022.json")).unwrap();
+
+        assert_eq!(file.id, Some(2022));
+        assert_eq!(file.folder, Some(folder.clone()));
+        assert!(!folder.join("2022.json").exists());
+        assert_eq!(
+            fs::read_to_string(quarantine_dir.join(&file.entry).join("2022.json")).unwrap(),
+            "{"
+        );
+
+        let listed = list_quarantined_guides(&quarantine_dir).unwrap();
+
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].entry, file.entry);
+        assert_eq!(listed[0].folder, Some(folder));
+    }
+
+    #[test]
+    fn is_valid_entry_name_rejects_paths() {
+        assert!(is_valid_entry_name("1718000000000-2022"));
+        assert!(!is_valid_entry_name("../guides"));
+        assert!(!is_valid_entry_name(""));
+    }
+}
@@ -0,0 +1,303 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use log::{debug, warn};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest;
+
+use crate::{
+    almanax::{get_experience_reward, get_kamas_reward},
+    guides::{extract_guide_quests, Error, GameType, SummaryQuestStatus},
+    quest::Quest,
+};
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct QuestRewards {
+    pub name: String,
+    pub quest_id: u32,
+    pub experience: u32,
+    pub kamas: u32,
+}
+
+/// Rewards of the quests completed at a step, `step` is a step index.
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct StepRewards {
+    pub step: u32,
+    pub quests: Vec<QuestRewards>,
+    pub experience: u32,
+    pub kamas: u32,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideRewards {
+    pub guide_id: u32,
+    /// Level of the profile in use the rewards are estimated for.
+    pub level: u32,
+    /// Steps completing at least one quest.
+    pub steps: Vec<StepRewards>,
+    pub experience: u32,
+    pub kamas: u32,
+    /// Completed quests not found on DofusDB, they are left out of the totals.
+    pub unresolved_quests: Vec<String>,
+}
+
+/// DofusDB quests looked up by the reward estimations, kept for the lifetime of the app.
+#[derive(Default)]
+pub struct QuestRewardsCache {
+    /// Quest ids by guide lang and quest name, `None` when DofusDB does not know the quest.
+    ids: Mutex<HashMap<(String, String), Option<u32>>>,
+    quests: Mutex<HashMap<u32, Arc<Quest>>>,
+}
+
+// Implementations
+
+impl QuestRewardsCache {
+    async fn quest(
+        &self,
+        name: &str,
+        lang: &str,
+        http_client: &reqwest::Client,
+    ) -> Result<Option<Arc<Quest>>, crate::quest::Error> {
+        let key = (lang.to_string(), name.to_string());
+        let cached_id = self.ids.lock().unwrap().get(&key).copied();
+
+        let id = match cached_id {
+            Some(id) => id,
+            None => {
+                let id = crate::quest::find_quest_id(name, lang, http_client).await?;
+                self.ids.lock().unwrap().insert(key, id);
+                id
+            }
+        };
+
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        if let Some(quest) = self.quests.lock().unwrap().get(&id) {
+            return Ok(Some(quest.clone()));
+        }
+
+        debug!("[GuideRewards] fetching quest {}", id);
+
+        let quest = Arc::new(crate::quest::get_quest(&id, http_client).await?);
+        self.quests.lock().unwrap().insert(id, quest.clone());
+
+        Ok(Some(quest))
+    }
+}
+
+// Public Functions
+
+/// Experience and kamas earned at `level` by completing every step of a quest.
+pub fn quest_rewards(quest: &Quest, level: u32) -> (u32, u32) {
+    quest
+        .steps
+        .iter()
+        .filter_map(|step| step.rewards.first().map(|reward| (step, reward)))
+        .fold((0u32, 0u32), |(experience, kamas), (step, reward)| {
+            (
+                experience.saturating_add(get_experience_reward(
+                    level,
+                    step.optimal_level,
+                    reward.experience_ratio,
+                    step.duration,
+                )),
+                kamas.saturating_add(get_kamas_reward(
+                    level,
+                    reward.level_max,
+                    step.optimal_level,
+                    reward.kamas_ratio,
+                    step.duration,
+                    reward.kamas_scale_with_player_level,
+                )),
+            )
+        })
+}
+
+/// Estimated rewards of the quests completed by a guide for the level of the profile in use, the
+/// quests of Wakfu guides are not on DofusDB and stay unresolved.
+pub async fn get_guide_rewards<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<GuideRewards, Error> {
+    let guide = crate::guides::get_installed_guide(app_handle, guide_id)?;
+    let conf = crate::conf::get_conf(app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+    let level = conf
+        .profiles
+        .iter()
+        .find(|p| p.id == conf.profile_in_use)
+        .map(|profile| profile.level)
+        .ok_or_else(|| Error::Profile(format!("profile not found: {}", conf.profile_in_use)))?;
+
+    let http_client = app_handle.state::<reqwest::Client>();
+    let cache = app_handle.state::<QuestRewardsCache>();
+    let lang = guide.lang.to_str();
+
+    let mut steps: Vec<StepRewards> = vec![];
+    let mut unresolved_quests = vec![];
+
+    for summary in extract_guide_quests(&guide) {
+        let completed_steps = summary
+            .statuses
+            .iter()
+            .filter_map(|status| match status {
+                SummaryQuestStatus::Completed(step_number) => Some(step_number - 1),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // a quest is only counted once, at the first step completing it
+        let Some(step) = completed_steps.into_iter().min() else {
+            continue;
+        };
+
+        let quest = match guide.game_type {
+            GameType::Dofus => cache
+                .quest(&summary.name, lang, &http_client)
+                .await
+                .unwrap_or_else(|err| {
+                    warn!(
+                        "[GuideRewards] cannot resolve quest {}: {}",
+                        summary.name, err
+                    );
+                    None
+                }),
+            GameType::Wakfu => None,
+        };
+
+        let Some(quest) = quest else {
+            unresolved_quests.push(summary.name);
+            continue;
+        };
+
+        let (experience, kamas) = quest_rewards(&quest, level);
+
+        add_quest_rewards(
+            &mut steps,
+            step,
+            QuestRewards {
+                name: summary.name,
+                quest_id: quest.id,
+                experience,
+                kamas,
+            },
+        );
+    }
+
+    steps.sort_by_key(|step| step.step);
+
+    Ok(GuideRewards {
+        guide_id,
+        level,
+        experience: steps
+            .iter()
+            .fold(0, |total, step| total.saturating_add(step.experience)),
+        kamas: steps
+            .iter()
+            .fold(0, |total, step| total.saturating_add(step.kamas)),
+        steps,
+        unresolved_quests,
+    })
+}
+
+// Private Functions
+
+fn add_quest_rewards(steps: &mut Vec<StepRewards>, step: u32, quest: QuestRewards) {
+    let index = match steps.iter().position(|s| s.step == step) {
+        Some(index) => index,
+        None => {
+            steps.push(StepRewards {
+                step,
+                quests: vec![],
+                experience: 0,
+                kamas: 0,
+            });
+            steps.len() - 1
+        }
+    };
+
+    let step_rewards = &mut steps[index];
+
+    step_rewards.experience = step_rewards.experience.saturating_add(quest.experience);
+    step_rewards.kamas = step_rewards.kamas.saturating_add(quest.kamas);
+    step_rewards.quests.push(quest);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_quest_rewards, quest_rewards, QuestRewards};
+    use crate::quest::{Quest, QuestDescription, QuestReward, QuestStep};
+
+    /// Step of a level 20 quest rewarding the base experience and kamas.
+    fn quest_step() -> QuestStep {
+        QuestStep {
+            description: QuestDescription {
+                en: String::new(),
+                es: String::new(),
+                fr: String::new(),
+                pt: String::new(),
+            },
+            optimal_level: 20,
+            duration: 1.0,
+            objectives: vec![],
+            rewards: vec![QuestReward {
+                level_max: -1,
+                kamas_ratio: 1.0,
+                experience_ratio: 1.0,
+                kamas_scale_with_player_level: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn quest_rewards_sums_every_quest_step() {
+        let one_step = Quest {
+            id: 1,
+            steps: vec![quest_step()],
+        };
+        let two_steps = Quest {
+            id: 2,
+            steps: vec![quest_step(), quest_step()],
+        };
+
+        let (experience, kamas) = quest_rewards(&one_step, 20);
+
+        assert!(experience > 0);
+        assert_eq!(kamas, 20 * 20 + 20 * 20 - 20);
+        assert_eq!(quest_rewards(&two_steps, 20), (experience * 2, kamas * 2));
+    }
+
+    #[test]
+    fn add_quest_rewards_groups_quests_by_step() {
+        let mut steps = vec![];
+
+        for (step, quest_id) in [(3, 1), (1, 2), (3, 3)] {
+            add_quest_rewards(
+                &mut steps,
+                step,
+                QuestRewards {
+                    name: quest_id.to_string(),
+                    quest_id,
+                    experience: 10,
+                    kamas: 5,
+                },
+            );
+        }
+
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].step, 3);
+        assert_eq!(steps[0].quests.len(), 2);
+        assert_eq!((steps[0].experience, steps[0].kamas), (20, 10));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    guides::{Error, Guide, GuideWithSteps, GuidesEventTrigger},
+    storage::ReadError,
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Enums
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum UpdatePolicy {
+    /// Updated by the batch updater, at launch or when every guide is updated.
+    #[default]
+    Auto,
+    /// Available updates are reported by `guideUpdatesAvailable` and only installed once confirmed.
+    NotifyOnly,
+    /// Never updated by the batch updater.
+    Frozen,
+}
+
+// Structs
+
+/// Update policy of the guides not updated automatically, by guide id.
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+pub struct GuideUpdatePolicies {
+    pub guides: HashMap<u32, UpdatePolicy>,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableGuideUpdate {
+    pub id: u32,
+    pub name: String,
+    /// `updated_at` of the guide on the server.
+    pub updated_at: Option<String>,
+}
+
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideRollback {
+    pub guide: GuideWithSteps,
+    /// Update policy of the guide after the rollback, a guide updated automatically is frozen so
+    /// the next update does not undo the rollback.
+    pub update_policy: UpdatePolicy,
+}
+
+// Implementations
+
+impl GuideUpdatePolicies {
+    pub fn policy(&self, guide_id: u32) -> UpdatePolicy {
+        self.guides.get(&guide_id).cloned().unwrap_or_default()
+    }
+}
+
+// Public Functions
+
+pub fn get_update_policies<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<GuideUpdatePolicies, Error> {
+    let path = app_handle.path().app_guide_update_policies_file();
+
+    match crate::storage::read_json::<GuideUpdatePolicies>(&path) {
+        Ok(policies) => Ok(policies.unwrap_or_default()),
+        Err(ReadError::Io(err)) => Err(Error::ReadUpdatePolicies(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::ReadUpdatePolicies(err.to_string())),
+    }
+}
+
+pub fn set_update_policy<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    policy: UpdatePolicy,
+) -> Result<GuideUpdatePolicies, Error> {
+    let mut policies = get_update_policies(app_handle)?;
+
+    info!(
+        "[GuideUpdatePolicy] guide {} policy set to {:?}",
+        guide_id, policy
+    );
+
+    // auto is the default, only the other policies are stored
+    if policy == UpdatePolicy::Auto {
+        policies.guides.remove(&guide_id);
+    } else {
+        policies.guides.insert(guide_id, policy);
+    }
+
+    let json = crate::json::serialize_pretty(&policies).map_err(Error::SerializeGuide)?;
+
+    crate::storage::write(&app_handle.path().app_guide_update_policies_file(), json)
+        .map_err(|err| Error::WriteUpdatePolicies(err.to_string()))?;
+
+    Ok(policies)
+}
+
+/// Freeze a guide rolled back to a previous version when it was updated automatically, and
+/// return its policy.
+pub fn freeze_rolled_back_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<UpdatePolicy, Error> {
+    let policy = get_update_policies(app_handle)?.policy(guide_id);
+    let rolled_back_policy = policy_after_rollback(guide_id, policy.clone());
+
+    if rolled_back_policy != policy {
+        set_update_policy(app_handle, guide_id, rolled_back_policy.clone())?;
+    }
+
+    Ok(rolled_back_policy)
+}
+
+/// Installed guides whose version on the server is different.
+pub fn available_updates(
+    installed: &[GuideWithSteps],
+    server_guides: &[Guide],
+) -> Vec<AvailableGuideUpdate> {
+    server_guides
+        .iter()
+        .filter(|guide| {
+            installed.iter().any(|installed| {
+                installed.id == guide.id && installed.updated_at != guide.updated_at
+            })
+        })
+        .map(|guide| AvailableGuideUpdate {
+            id: guide.id,
+            name: guide.name.clone(),
+            updated_at: guide.updated_at.clone(),
+        })
+        .collect()
+}
+
+/// Emit `guideUpdatesAvailable` with the guides of `guide_ids` having an update on the server.
+pub async fn notify_available_updates<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    installed: &[GuideWithSteps],
+    guide_ids: &[u32],
+) {
+    let server_guides = match crate::guide_catalog::get_catalog(app_handle, None).await {
+        Ok(catalog) => catalog.guides,
+        Err(err) => {
+            warn!(
+                "[GuideUpdatePolicy] cannot check the available updates: {}",
+                err
+            );
+            return;
+        }
+    };
+
+    let updates = available_updates(installed, &server_guides)
+        .into_iter()
+        .filter(|update| guide_ids.contains(&update.id))
+        .collect::<Vec<_>>();
+
+    if updates.is_empty() {
+        return;
+    }
+
+    info!(
+        "[GuideUpdatePolicy] {} guide updates waiting for confirmation",
+        updates.len()
+    );
+
+    if let Err(err) = GuidesEventTrigger::new(app_handle.clone()).guide_updates_available(updates) {
+        warn!(
+            "[GuideUpdatePolicy] failed to emit guide_updates_available event: {:?}",
+            err
+        );
+    }
+}
+
+// Private Functions
+
+/// Local guides are never updated, they keep their policy.
+fn policy_after_rollback(guide_id: u32, policy: UpdatePolicy) -> UpdatePolicy {
+    if policy == UpdatePolicy::Auto && !crate::guides::is_local_guide(guide_id) {
+        UpdatePolicy::Frozen
+    } else {
+        policy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{available_updates, policy_after_rollback, GuideUpdatePolicies, UpdatePolicy};
+    use crate::{guides::LOCAL_GUIDE_ID_START, test_guides::guide};
+
+    #[test]
+    fn available_updates_only_lists_installed_guides_which_changed() {
+        let updates = available_updates(
+            &[
+                guide(1).updated_at("a").build(),
+                guide(2).updated_at("b").build(),
+            ],
+            &[
+                guide(1).updated_at("a").summary(),
+                guide(2).updated_at("c").summary(),
+                guide(3).updated_at("d").summary(),
+            ],
+        );
+
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].id, 2);
+        assert_eq!(updates[0].updated_at.as_deref(), Some("c"));
+
+        let policies = GuideUpdatePolicies {
+            guides: [(2, UpdatePolicy::Frozen)].into(),
+        };
+
+        assert_eq!(policies.policy(1), UpdatePolicy::Auto);
+        assert_eq!(policies.policy(2), UpdatePolicy::Frozen);
+    }
+
+    #[test]
+    fn policy_after_rollback_freezes_server_guides_updated_automatically() {
+        assert_eq!(
+            policy_after_rollback(1, UpdatePolicy::Auto),
+            UpdatePolicy::Frozen
+        );
+        assert_eq!(
+            policy_after_rollback(1, UpdatePolicy::NotifyOnly),
+            UpdatePolicy::NotifyOnly
+        );
+        assert_eq!(
+            policy_after_rollback(LOCAL_GUIDE_ID_START, UpdatePolicy::Auto),
+            UpdatePolicy::Auto
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, info, warn};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    guide_diff::{StepChange, StepMapping},
+    guides::{Error, GuideWithSteps},
+    tauri_api_ext::GuidesPathExt,
+};
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideVersion {
+    pub version: String,
+    pub saved_at: String,
+    pub updated_at: Option<String>,
+    pub steps_count: u32,
+}
+
+// Public Functions
+
+/// Keep the previous content of a guide as a versioned snapshot before it gets replaced, when it
+/// actually changed.
+pub fn snapshot_replaced_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    previous: &GuideWithSteps,
+    next: &GuideWithSteps,
+) -> Result<(), Error> {
+    save_version(
+        &guide_versions_dir(app_handle, previous.id),
+        previous,
+        next,
+        guide_versions_retention(app_handle),
+    )
+    .map(|_| ())
+}
+
+/// List the saved versions of a guide, the most recent first.
+pub fn get_guide_versions<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<Vec<GuideVersion>, Error> {
+    let dir = guide_versions_dir(app_handle, guide_id);
+    let mut versions = vec![];
+
+    for version in list_version_ids(&dir)? {
+        let guide = read_version(&dir, &version)?;
+        let saved_at = version
+            .parse::<i64>()
+            .ok()
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|date| date.to_rfc3339())
+            .unwrap_or_default();
+
+        versions.push(GuideVersion {
+            version,
+            saved_at,
+            updated_at: guide.updated_at,
+            steps_count: guide.steps.len() as u32,
+        });
+    }
+
+    versions.reverse();
+
+    Ok(versions)
+}
+
+/// Step by step changes between two versions of a guide. `None` stands for the installed guide.
+pub fn diff_guide_versions<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<StepChange>, Error> {
+    let dir = guide_versions_dir(app_handle, guide_id);
+    let load = |version: Option<String>| match version {
+        Some(version) => read_version(&dir, &version),
+        None => crate::guides::get_installed_guide(app_handle, guide_id),
+    };
+
+    Ok(diff_guides(&load(from)?, &load(to)?))
+}
+
+/// Replace the installed guide with one of its saved versions. The installed guide is itself
+/// saved as a version first so the rollback can be undone.
+pub fn rollback_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    version: String,
+) -> Result<GuideWithSteps, Error> {
+    info!(
+        "[GuideVersions] rolling back guide {} to version {}",
+        guide_id, version
+    );
+
+    let installed = crate::guides::get_installed_guide(app_handle, guide_id)?;
+    let restored = restore_version(
+        &guide_versions_dir(app_handle, guide_id),
+        &installed,
+        &version,
+        guide_versions_retention(app_handle),
+    )?;
+
+    crate::guides::write_guide_and_remap(app_handle, &installed, restored.clone())?;
+
+    Ok(restored)
+}
+
+// Private Functions
+
+fn guide_versions_dir<R: Runtime>(app_handle: &AppHandle<R>, guide_id: u32) -> PathBuf {
+    app_handle
+        .path()
+        .app_guide_versions_dir()
+        .join(guide_id.to_string())
+}
+
+fn guide_versions_retention<R: Runtime>(app_handle: &AppHandle<R>) -> usize {
+    crate::conf::get_conf(app_handle)
+        .map(|conf| conf.guide_versions_retention)
+        .unwrap_or(crate::conf::DEFAULT_GUIDE_VERSIONS_RETENTION) as usize
+}
+
+/// Save `previous` as a version in `dir` when `next` changes its content, then drop the oldest
+/// versions beyond `retention`. Returns the id of the saved version.
+fn save_version(
+    dir: &Path,
+    previous: &GuideWithSteps,
+    next: &GuideWithSteps,
+    retention: usize,
+) -> Result<Option<String>, Error> {
+    if retention == 0 {
+        return Ok(None);
+    }
+
+    // the folder is not part of the guide content
+    let mut next = next.clone();
+    next.folder = previous.folder.clone();
+
+    let previous_json = crate::json::serialize_pretty(previous).map_err(Error::SerializeGuide)?;
+    let next_json = crate::json::serialize_pretty(&next).map_err(Error::SerializeGuide)?;
+
+    if previous_json == next_json {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(dir).map_err(|err| Error::WriteGuideVersion(err.to_string()))?;
+
+    // versions saved in the same millisecond, like an update followed by a rollback, are put
+    // after the latest one instead of overwriting it or, once it is pruned, an older one
+    let latest = list_version_ids(dir)?
+        .last()
+        .and_then(|version| version.parse::<i64>().ok());
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let version = match latest {
+        Some(latest) if latest >= timestamp => latest + 1,
+        _ => timestamp,
+    }
+    .to_string();
+    let file = dir.join(format!("{}.json", version));
+
+    debug!(
+        "[GuideVersions] saving version {} of guide {} to {:?}",
+        version, previous.id, file
+    );
+
+    crate::storage::write(&file, previous_json)
+        .map_err(|err| Error::WriteGuideVersion(err.to_string()))?;
+
+    prune_versions(dir, previous.id, retention)?;
+
+    Ok(Some(version))
+}
+
+fn read_version(dir: &Path, version: &str) -> Result<GuideWithSteps, Error> {
+    if version.is_empty() || !version.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::GuideVersionNotFound(version.to_string()));
+    }
+
+    let content =
+        fs::read_to_string(dir.join(format!("{}.json", version))).map_err(|err| {
+            match err.kind() {
+                std::io::ErrorKind::NotFound => Error::GuideVersionNotFound(version.to_string()),
+                _ => Error::ReadGuideVersion(err.to_string()),
+            }
+        })?;
+
+    crate::json::from_str::<GuideWithSteps>(&content).map_err(Error::GuideWithStepsMalformed)
+}
+
+/// Read a version of `installed` to restore it in its folder, `installed` is saved as a version
+/// first.
+fn restore_version(
+    dir: &Path,
+    installed: &GuideWithSteps,
+    version: &str,
+    retention: usize,
+) -> Result<GuideWithSteps, Error> {
+    let mut restored = read_version(dir, version)?;
+
+    restored.folder = installed.folder.clone();
+
+    if let Err(err) = save_version(dir, installed, &restored, retention) {
+        warn!(
+            "[GuideVersions] failed to save version of guide {} before rollback: {}",
+            installed.id, err
+        );
+    }
+
+    Ok(restored)
+}
+
+fn diff_guides(from: &GuideWithSteps, to: &GuideWithSteps) -> Vec<StepChange> {
+    StepMapping::between(&from.steps, &to.steps).changes()
+}
+
+/// Version ids saved in `dir`, the oldest first.
+fn list_version_ids(dir: &Path) -> Result<Vec<String>, Error> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut versions = fs::read_dir(dir)
+        .map_err(|err| Error::ReadGuideVersion(err.to_string()))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+
+            if path.extension()? != "json" {
+                return None;
+            }
+
+            let stem = path.file_stem()?.to_str()?;
+
+            stem.parse::<u64>().ok().map(|_| stem.to_string())
+        })
+        .collect::<Vec<_>>();
+
+    versions.sort_by_key(|version| version.parse::<u64>().unwrap_or_default());
+
+    Ok(versions)
+}
+
+fn prune_versions(dir: &Path, guide_id: u32, retention: usize) -> Result<(), Error> {
+    let versions = list_version_ids(dir)?;
+
+    if versions.len() <= retention {
+        return Ok(());
+    }
+
+    for version in &versions[..versions.len() - retention] {
+        debug!(
+            "[GuideVersions] removing old version {} of guide {}",
+            version, guide_id
+        );
+
+        if let Err(err) = fs::remove_file(dir.join(format!("{}.json", version))) {
+            warn!(
+                "[GuideVersions] failed to remove old version {} of guide {}: {}",
+                version, guide_id, err
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_guides, list_version_ids, read_version, restore_version, save_version};
+    use crate::{
+        guide_diff::StepChange,
+        test_guides::{guide, step_at},
+    };
+
+    #[test]
+    fn save_version_keeps_the_most_recent_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let versions = (0..4)
+            .map(|index| {
+                let previous = guide(1).steps(vec![step_at("a", index, 0)]).build();
+                let next = guide(1).steps(vec![step_at("a", index + 1, 0)]).build();
+
+                save_version(dir.path(), &previous, &next, 2)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // saved in the same millisecond, the versions still get their own file
+        assert_eq!(list_version_ids(dir.path()).unwrap(), versions[2..]);
+        assert_eq!(
+            read_version(dir.path(), &versions[3]).unwrap().steps[0].pos_x,
+            3
+        );
+
+        let unchanged = guide(1).build();
+
+        assert!(save_version(dir.path(), &unchanged, &unchanged, 2)
+            .unwrap()
+            .is_none());
+        assert!(save_version(dir.path(), &unchanged, &guide(2).build(), 0)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn diff_guides_lists_step_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = guide(1).steps(vec![step_at("a", 0, 0)]).build();
+        let new = guide(1)
+            .steps(vec![step_at("a", 0, 0), step_at("b", 1, 1)])
+            .build();
+
+        let version = save_version(dir.path(), &old, &new, 5).unwrap().unwrap();
+
+        assert_eq!(
+            diff_guides(&read_version(dir.path(), &version).unwrap(), &new),
+            vec![
+                StepChange::Unchanged { from: 0, to: 0 },
+                StepChange::Added { to: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn restore_version_can_be_undone() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut old = guide(1).steps(vec![step_at("old", 0, 0)]).build();
+        let mut new = guide(1).steps(vec![step_at("new", 0, 0)]).build();
+
+        old.folder = Some(dir.path().join("before"));
+        new.folder = Some(dir.path().join("after"));
+
+        let old_version = save_version(dir.path(), &old, &new, 5).unwrap().unwrap();
+        let restored = restore_version(dir.path(), &new, &old_version, 5).unwrap();
+
+        assert_eq!(restored.steps[0].web_text, "old");
+        assert_eq!(restored.folder, new.folder);
+
+        let versions = list_version_ids(dir.path()).unwrap();
+
+        assert_eq!(versions.len(), 2);
+
+        let undone = restore_version(dir.path(), &restored, &versions[1], 5).unwrap();
+
+        assert_eq!(undone.steps[0].web_text, "new");
+        assert!(read_version(dir.path(), "../1").is_err());
+    }
+}
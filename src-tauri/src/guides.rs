@@ -1,7 +1,9 @@
 use std::{
     collections::{HashMap, HashSet},
     fmt, fs,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::Duration,
     vec,
 };
 
@@ -10,11 +12,47 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_http::reqwest;
 use tauri_plugin_opener::OpenerExt;
-
-use crate::{api::GANYMEDE_API, tauri_api_ext::GuidesPathExt};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    api::GANYMEDE_API,
+    guide_bundle::{BundleConflictStrategy, BundleExport, BundleImport},
+    guide_catalog::{
+        get_catalog, mark_catalog_visited, query_guides, GuideCatalog, GuideQuery, GuidesPage,
+    },
+    guide_changelog::{get_last_changelog, publish_changelog, GuidesChangelog},
+    guide_diff::{remap_progress, remap_step_notes, StepChange, StepMapping},
+    guide_headers::{read_listed_guide, GuideHeaderOrFolder},
+    guide_library::GuidesLibrary,
+    guide_links::{GuideDependencies, LinkedGuidesDownload},
+    guide_lint::GuideLintReport,
+    guide_quarantine::{
+        quarantine_guide_file, recover_quarantined_guides_in_background, MalformedGuidesReport,
+        QuarantinedGuideFile,
+    },
+    guide_rewards::GuideRewards,
+    guide_update_policy::{
+        available_updates, freeze_rolled_back_guide, get_update_policies, notify_available_updates,
+        set_update_policy, AvailableGuideUpdate, GuideRollback, GuideUpdatePolicies, UpdatePolicy,
+    },
+    guide_versions::{snapshot_replaced_guide, GuideVersion},
+    image_cache::OfflineGuideImages,
+    quest_index::{GuidesQuestIndex, IndexedQuest, QuestCompletion},
+    search::{GuideSearchResult, GuidesSearchIndex, MAX_SEARCH_RESULTS},
+    shopping_list::ShoppingListEntry,
+    storage::ReadError,
+    tauri_api_ext::GuidesPathExt,
+};
 
 pub const DEFAULT_GUIDE_ID: u32 = 1074;
+/// Ids from this one are reserved to guides authored locally, the server never gives them out.
+pub const LOCAL_GUIDE_ID_START: u32 = 2_000_000_000;
 const MAX_RECENT_GUIDES: usize = 50;
+/// Ids per batch request, keeps the url far from the length limits.
+const BATCH_CHUNK_SIZE: usize = 50;
+const BATCH_CONCURRENCY: usize = 4;
+const BATCH_MAX_ATTEMPTS: u32 = 4;
+const BATCH_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
 
 // ================================================================================================
 // Enums
@@ -71,9 +109,61 @@ pub enum Error {
     Opener(String),
     #[error("server unreachable")]
     NetworkUnavailable,
+    #[error("cannot write a guide version: {0}")]
+    WriteGuideVersion(String),
+    #[error("cannot read a guide version: {0}")]
+    ReadGuideVersion(String),
+    #[error("guide version not found: {0}")]
+    GuideVersionNotFound(String),
+    #[error("cannot quarantine a guide file: {0}")]
+    QuarantineGuideFile(String),
+    #[error("cannot read the quarantine: {0}")]
+    ReadQuarantine(String),
+    #[error("quarantined guide not found: {0}")]
+    QuarantinedGuideNotFound(String),
+    #[error("quarantined guide cannot be recovered: {0}")]
+    QuarantinedGuideUnrecoverable(String),
+    #[error("cannot purge the quarantine: {0}")]
+    PurgeQuarantine(String),
+    #[error("invalid folder: {0}")]
+    InvalidFolder(String),
+    #[error("cannot read a guide bundle: {0}")]
+    ReadBundle(String),
+    #[error("cannot write a guide bundle: {0}")]
+    WriteBundle(String),
+    #[error("malformed guide bundle: {0}")]
+    BundleMalformed(crate::json::Error),
+    #[error("unsupported guide bundle version: {0}")]
+    UnsupportedBundleVersion(u32),
+    #[error("cannot read or write profile data: {0}")]
+    Profile(String),
+    #[error("not a local guide: {0}")]
+    NotALocalGuide(u32),
+    #[error("no local guide id left")]
+    LocalGuideIdsExhausted,
+    #[error("step index out of range: {0}")]
+    StepOutOfRange(u32),
+    #[error("folder not found: {0}")]
+    FolderNotFound(String),
+    #[error("folder already exists: {0}")]
+    FolderAlreadyExists(String),
+    #[error("cannot create folder: {0}")]
+    CreateFolder(String),
+    #[error("cannot move in system: {0}")]
+    MoveInSystem(String),
+    #[error("cannot read the guide update policies: {0}")]
+    ReadUpdatePolicies(String),
+    #[error("cannot write the guide update policies: {0}")]
+    WriteUpdatePolicies(String),
+    #[error("cannot read the guides changelog: {0}")]
+    ReadChangelog(String),
+    #[error("cannot write the guides changelog: {0}")]
+    WriteChangelog(String),
+    #[error("cannot read the zaaps: {0}")]
+    ReadZaaps(String),
 }
 
-#[derive(Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub enum GuideLang {
     En,
@@ -82,7 +172,7 @@ pub enum GuideLang {
     Pt,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
 #[serde(rename_all = "lowercase")]
 pub enum GameType {
     #[default]
@@ -90,7 +180,7 @@ pub enum GameType {
     Wakfu,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub enum Status {
     Draft,
@@ -120,8 +210,14 @@ pub enum GuidesOrFolder {
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum UpdateAllAtOnceResult {
     Success,
-    Failure { message: String },
+    Failure {
+        message: String,
+    },
     Offline,
+    /// Not updated because of its update policy.
+    Skipped {
+        policy: UpdatePolicy,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, taurpc::specta::Type)]
@@ -232,7 +328,64 @@ pub struct QuestSummary {
     pub statuses: Vec<SummaryQuestStatus>,
 }
 
-pub type RecentGuides = HashMap<String, Vec<u32>>;
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuidesDownloadProgress {
+    pub done: u32,
+    pub total: u32,
+}
+
+/// Steps of a guide which could not be matched after an update, the progress and notes attached
+/// to them have been moved to the closest previous step.
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct RemappedGuideSteps {
+    pub guide_id: u32,
+    pub unmapped_steps: Vec<u32>,
+}
+
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct RecentGuide {
+    pub id: u32,
+    /// `None` for the guides recorded before the opening times were.
+    pub last_opened_at: Option<String>,
+    /// Step the guide was on when it was last opened or closed.
+    pub step: u32,
+    pub time_spent_secs: u32,
+    /// Start of the session in progress, its time is added to `time_spent_secs` on close.
+    pub session_started_at: Option<String>,
+}
+
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRecentGuides {
+    /// Guides currently open, in the order of their tabs.
+    pub opened: Vec<u32>,
+    /// Every guide opened, the most recently opened last.
+    pub history: Vec<RecentGuide>,
+}
+
+/// Last guide opened by a profile, to resume it where it was left.
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideSession {
+    pub guide: GuideWithSteps,
+    pub step: u32,
+    pub progress: Option<crate::conf::Progress>,
+    pub last_opened_at: Option<String>,
+    pub time_spent_secs: u32,
+}
+
+pub type RecentGuides = HashMap<String, ProfileRecentGuides>;
+
+/// When the app was started. The guide sessions started before were left open by a previous run,
+/// the time spent in them is unknown.
+pub struct AppStartedAt(pub chrono::DateTime<chrono::Utc>);
 
 #[derive(Deserialize)]
 struct BatchGuideResponse {
@@ -244,7 +397,7 @@ struct BatchGuideResponse {
 // ================================================================================================
 
 impl Status {
-    fn to_str(&self) -> &'static str {
+    pub fn to_str(&self) -> &'static str {
         use Status::*;
 
         match self {
@@ -257,6 +410,36 @@ impl Status {
     }
 }
 
+impl GuideLang {
+    pub fn to_str(&self) -> &'static str {
+        use GuideLang::*;
+
+        match self {
+            En => "en",
+            Fr => "fr",
+            Es => "es",
+            Pt => "pt",
+        }
+    }
+}
+
+impl GameType {
+    pub fn to_str(&self) -> &'static str {
+        use GameType::*;
+
+        match self {
+            Dofus => "dofus",
+            Wakfu => "wakfu",
+        }
+    }
+}
+
+impl Default for AppStartedAt {
+    fn default() -> Self {
+        AppStartedAt(chrono::Utc::now())
+    }
+}
+
 impl Into<String> for Status {
     fn into(self) -> String {
         self.to_string()
@@ -288,9 +471,10 @@ pub fn ensure_guides_dir(app_handle: &AppHandle) -> Result<(), Error> {
 pub async fn download_default_guide(app: &AppHandle) -> Result<Guides, Error> {
     let mut guides = get_guides_from_handle(app, "".into())?;
 
-    download_guide_by_id(app, &mut guides, DEFAULT_GUIDE_ID, "".into()).await?;
+    let previous = download_guide_by_id(app, &mut guides, DEFAULT_GUIDE_ID, "".into()).await?;
 
     write_guides(&guides, app)?;
+    remap_profiles_after_update(app, previous.as_slice(), &guides.guides);
 
     Ok(guides)
 }
@@ -324,6 +508,10 @@ pub async fn get_guide_from_server(
 ) -> Result<GuideWithSteps, Error> {
     info!("[Guides] get_guide_from_server: {}", guide_id);
 
+    if is_local_guide(guide_id) {
+        return Err(Error::GuideNotFound(guide_id));
+    }
+
     let res = http_client
         .get(format!("{}/v2/guides/{}", GANYMEDE_API, guide_id))
         .send()
@@ -342,66 +530,93 @@ pub async fn get_guide_from_server(
     Ok(guide)
 }
 
-/// Fetch multiple guides from the server by their IDs in a single batch request
+/// Fetch guides from the server in chunks of ids requested concurrently, retrying the chunks
+/// failing with a transient error. `on_progress` receives the number of guides done and the
+/// total after each chunk.
 pub async fn get_guides_from_server(
     ids: Vec<u32>,
     http_client: &reqwest::Client,
+    mut on_progress: impl FnMut(u32, u32),
 ) -> Vec<Result<GuideWithSteps, Error>> {
     info!("[Guides] get_guides_from_server (batch): {:?}", ids);
 
-    let joined_ids = ids
+    // local guides are missing from the server response and reported as not found
+    let server_ids = ids
         .iter()
-        .map(|id| id.to_string())
-        .collect::<Vec<_>>()
-        .join(",");
+        .copied()
+        .filter(|id| !is_local_guide(*id))
+        .collect::<Vec<_>>();
+
+    if server_ids.is_empty() {
+        return ids
+            .iter()
+            .map(|id| Err(Error::GuideNotFound(*id)))
+            .collect();
+    }
 
-    let res = http_client
-        .get(format!(
-            "{}/v2/guides/batch?ids={}",
-            GANYMEDE_API, joined_ids
-        ))
-        .send()
-        .await;
+    let total = server_ids.len() as u32;
+    let semaphore = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+    let mut chunks = JoinSet::new();
+    // ids of each chunk by task, to report them as failed when their task panics
+    let mut chunk_ids = HashMap::new();
 
-    let response = match res {
-        Ok(response) => response,
-        Err(err) => {
-            let error = if err.is_connect() || err.is_timeout() {
-                Error::NetworkUnavailable
-            } else {
-                Error::RequestGuides(err.to_string())
-            };
-            return ids.iter().map(|_| Err(error.clone())).collect();
-        }
-    };
+    for chunk in server_ids.chunks(BATCH_CHUNK_SIZE) {
+        let chunk = chunk.to_vec();
+        let http_client = http_client.clone();
+        let semaphore = semaphore.clone();
 
-    let text = match response.text().await {
-        Ok(text) => text,
-        Err(err) => {
-            let error = Error::RequestGuidesContent(err.to_string());
-            return ids.iter().map(|_| Err(error.clone())).collect();
-        }
-    };
+        let task = chunks.spawn({
+            let chunk = chunk.clone();
 
-    let batch_response = match crate::json::from_str::<BatchGuideResponse>(text.as_str()) {
-        Ok(batch) => batch,
-        Err(err) => {
-            let error = Error::GuidesMalformed(err);
-            return ids.iter().map(|_| Err(error.clone())).collect();
-        }
-    };
+            async move {
+                let _permit = semaphore.acquire_owned().await;
+
+                request_guides_chunk_with_retry(&chunk, &http_client).await
+            }
+        });
+
+        chunk_ids.insert(task.id(), chunk);
+    }
 
     let mut guides_map: HashMap<u32, GuideWithSteps> = HashMap::new();
-    for mut guide in batch_response.data {
-        guide.folder = None;
-        guides_map.insert(guide.id, guide);
+    let mut errors_map: HashMap<u32, Error> = HashMap::new();
+    let mut done = 0;
+
+    while let Some(joined) = chunks.join_next_with_id().await {
+        let (chunk, result) = match joined {
+            Ok((task_id, result)) => (chunk_ids.remove(&task_id).unwrap_or_default(), result),
+            Err(err) => {
+                warn!("[Guides] guides batch chunk panicked: {}", err);
+
+                (
+                    chunk_ids.remove(&err.id()).unwrap_or_default(),
+                    Err(Error::RequestGuides(err.to_string())),
+                )
+            }
+        };
+
+        match result {
+            Ok(guides) => {
+                for mut guide in guides {
+                    guide.folder = None;
+                    guides_map.insert(guide.id, guide);
+                }
+            }
+            Err(error) => {
+                for id in &chunk {
+                    errors_map.insert(*id, error.clone());
+                }
+            }
+        }
+
+        done += chunk.len() as u32;
+        on_progress(done, total);
     }
 
     ids.iter()
-        .map(|id| {
-            guides_map
-                .remove(id)
-                .ok_or_else(|| Error::GuideNotFound(*id))
+        .map(|id| match guides_map.remove(id) {
+            Some(guide) => Ok(guide),
+            None => Err(errors_map.remove(id).unwrap_or(Error::GuideNotFound(*id))),
         })
         .collect()
 }
@@ -414,16 +629,13 @@ fn get_guides_or_folder_from_handle<R: Runtime>(
     app: &AppHandle<R>,
     folder: Option<String>,
 ) -> Result<Vec<GuidesOrFolder>, Error> {
-    let mut guide_folder = app.path().app_guides_dir();
-
-    if let Some(folder) = folder {
-        guide_folder = guide_folder.join(folder);
-    }
+    let guide_folder = resolve_folder(&app.path().app_guides_dir(), &folder.unwrap_or_default())?;
 
     println!("[Guides] get_guides_or_folder in {:?}", guide_folder);
 
+    let quarantine_dir = app.path().app_guides_quarantine_dir();
     let mut result = vec![];
-    let mut removed = vec![];
+    let mut quarantined = vec![];
 
     for entry in fs::read_dir(guide_folder).map_err(|err| Error::ReadGuidesDir(err.to_string()))? {
         let entry = entry.map_err(|err| Error::ReadGuidesDir(err.to_string()))?;
@@ -435,7 +647,9 @@ fn get_guides_or_folder_from_handle<R: Runtime>(
         } else if path.is_file() {
             if let Some(ext) = path.extension() {
                 if ext == "json" {
-                    if let Some(guide) = parse_guide_or_remove(&path, &mut removed)? {
+                    if let Some(guide) =
+                        parse_guide_or_quarantine(&path, &quarantine_dir, &mut quarantined)?
+                    {
                         result.push(GuidesOrFolder::Guide(guide));
                     }
                 }
@@ -443,72 +657,50 @@ fn get_guides_or_folder_from_handle<R: Runtime>(
         }
     }
 
-    emit_malformed_guides_removed(app, removed);
+    recover_quarantined_guides_in_background(app, quarantined);
 
     Ok(result)
 }
 
-/// Parse a guide file. On a JSON parse error (e.g. empty or corrupted file), the
-/// file is removed from disk and recorded so a single malformed guide does not
-/// break loading the whole list. See issue #200.
-fn parse_guide_or_remove(
+/// Parse a guide file. A corrupted file is first recovered from its backup. On a JSON
+/// parse error (e.g. empty or corrupted file without backup), the file is moved to the
+/// quarantine and recorded so it can be downloaded again into the same folder, and a single
+/// malformed guide does not break loading the whole list. See issue #200.
+pub fn parse_guide_or_quarantine(
     file_path: &Path,
-    removed: &mut Vec<RemovedGuideFile>,
+    quarantine_dir: &Path,
+    quarantined: &mut Vec<QuarantinedGuideFile>,
 ) -> Result<Option<GuideWithSteps>, Error> {
-    let content =
-        fs::read_to_string(file_path).map_err(|err| Error::ReadGuideFile(err.to_string()))?;
-
-    match crate::json::from_str::<GuideWithSteps>(content.as_str()) {
-        Ok(mut guide) => {
+    match crate::storage::read_json::<GuideWithSteps>(file_path) {
+        Ok(Some(mut guide)) => {
             guide.folder = Some(file_path.parent().unwrap().to_path_buf());
             Ok(Some(guide))
         }
-        Err(err) => {
+        Ok(None) => Ok(None),
+        Err(ReadError::Io(err)) => Err(Error::ReadGuideFile(err)),
+        Err(ReadError::Malformed(err)) => {
             warn!(
-                "[Guides] removing malformed guide file {:?}: {}",
+                "[Guides] quarantining malformed guide file {:?}: {}",
                 file_path, err
             );
 
-            if let Err(remove_err) = fs::remove_file(file_path) {
-                warn!(
-                    "[Guides] failed to remove malformed guide file {:?}: {}",
-                    file_path, remove_err
-                );
+            match quarantine_guide_file(quarantine_dir, file_path) {
+                Ok(file) => quarantined.push(file),
+                Err(quarantine_err) => warn!(
+                    "[Guides] failed to quarantine malformed guide file {:?}: {}",
+                    file_path, quarantine_err
+                ),
             }
 
-            removed.push(RemovedGuideFile {
-                id: file_path
-                    .file_stem()
-                    .and_then(|stem| stem.to_str())
-                    .and_then(|stem| stem.parse::<u32>().ok()),
-                file_name: file_path
-                    .file_name()
-                    .and_then(|name| name.to_str())
-                    .unwrap_or_default()
-                    .to_string(),
-            });
-
             Ok(None)
         }
     }
 }
 
-fn emit_malformed_guides_removed<R: Runtime>(app: &AppHandle<R>, removed: Vec<RemovedGuideFile>) {
-    if removed.is_empty() {
-        return;
-    }
-
-    let trigger = GuidesEventTrigger::new(app.clone());
-
-    if let Err(err) = trigger.malformed_guides_removed(removed) {
-        warn!(
-            "[Guides] failed to emit malformed_guides_removed event: {:?}",
-            err
-        );
-    }
-}
-
-fn get_guides_from_path(path_buf: &PathBuf) -> Result<(Guides, Vec<RemovedGuideFile>), Error> {
+fn get_guides_from_path(
+    path_buf: &PathBuf,
+    quarantine_dir: &Path,
+) -> Result<(Guides, Vec<QuarantinedGuideFile>), Error> {
     info!("[Guides] get_guides in {:?}", path_buf);
 
     let options = glob::MatchOptions {
@@ -521,12 +713,13 @@ fn get_guides_from_path(path_buf: &PathBuf) -> Result<(Guides, Vec<RemovedGuideF
         .map_err(|err| Error::Pattern(err.to_string()))?;
 
     let mut guides = vec![];
-    let mut removed = vec![];
+    let mut quarantined = vec![];
 
     for entry in files {
         let file_path = entry.map_err(|err| Error::ReadGuidesDirGlob(err.to_string()))?;
 
-        let Some(guide) = parse_guide_or_remove(&file_path, &mut removed)? else {
+        let Some(guide) = parse_guide_or_quarantine(&file_path, quarantine_dir, &mut quarantined)?
+        else {
             continue;
         };
 
@@ -537,24 +730,40 @@ fn get_guides_from_path(path_buf: &PathBuf) -> Result<(Guides, Vec<RemovedGuideF
         guides.push(guide);
     }
 
-    Ok((Guides { guides }, removed))
+    Ok((Guides { guides }, quarantined))
 }
 
-fn get_guides_from_handle<R: Runtime>(app: &AppHandle<R>, folder: String) -> Result<Guides, Error> {
-    let mut guides_dir = app.path().app_guides_dir();
+pub fn get_guides_from_handle<R: Runtime>(
+    app: &AppHandle<R>,
+    folder: String,
+) -> Result<Guides, Error> {
+    let guides_dir = resolve_folder(&app.path().app_guides_dir(), &folder)?;
+    let library = app.state::<GuidesLibrary>();
 
-    if folder != "" {
-        guides_dir = guides_dir.join(folder);
+    if let Some(guides) = library.guides_in(&guides_dir) {
+        return Ok(Guides { guides });
     }
 
-    let (guides, removed) = get_guides_from_path(&guides_dir)?;
+    let quarantine_dir = app.path().app_guides_quarantine_dir();
+    let (guides, quarantined) =
+        get_guides_from_path(&app.path().app_guides_dir(), &quarantine_dir)?;
 
-    emit_malformed_guides_removed(app, removed);
+    // the malformed files have been moved out of the guides directory
+    for file in &quarantined {
+        if let Some(folder) = &file.folder {
+            library.record_app_change(&folder.join(&file.file_name));
+        }
+    }
 
-    Ok(guides)
+    recover_quarantined_guides_in_background(app, quarantined);
+    library.rebuild(guides.guides);
+
+    Ok(Guides {
+        guides: library.guides_in(&guides_dir).unwrap_or_default(),
+    })
 }
 
-fn write_guides<R: Runtime>(guides: &Guides, app: &AppHandle<R>) -> Result<(), Error> {
+pub fn write_guides<R: Runtime>(guides: &Guides, app: &AppHandle<R>) -> Result<(), Error> {
     let guides_dir = &app.path().app_guides_dir();
 
     for guide in &guides.guides {
@@ -573,12 +782,288 @@ fn write_guides<R: Runtime>(guides: &Guides, app: &AppHandle<R>) -> Result<(), E
 
         debug!("Writing guide to {:?}", file);
 
-        fs::write(file.as_path(), json).map_err(|err| Error::WriteGuideFile(err.to_string()))?;
+        app.state::<GuidesLibrary>().record_app_change(&file);
+        crate::storage::write(&file, json).map_err(|err| Error::WriteGuideFile(err.to_string()))?;
+
+        app.state::<GuidesLibrary>().upsert(guide);
+    }
+
+    Ok(())
+}
+
+/// Installed guide with the given id, wherever it is stored in the guides directory
+pub fn get_installed_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<GuideWithSteps, Error> {
+    let library = app_handle.state::<GuidesLibrary>();
+
+    if library.is_loaded() {
+        return library
+            .guide(guide_id)
+            .ok_or(Error::GetGuideInSystem(guide_id));
+    }
+
+    // only the file of the guide is parsed when it has already been listed
+    if let Some(guide) = read_listed_guide(app_handle, guide_id) {
+        return Ok(guide);
     }
 
+    get_guides_from_handle(app_handle, "".to_string())?
+        .guides
+        .into_iter()
+        .find(|g| g.id == guide_id)
+        .ok_or(Error::GetGuideInSystem(guide_id))
+}
+
+/// Replace an installed guide and move every profile progress to its new steps
+pub fn write_guide_and_remap<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    previous: &GuideWithSteps,
+    guide: GuideWithSteps,
+) -> Result<(), Error> {
+    let guides = Guides {
+        guides: vec![guide],
+    };
+
+    write_guides(&guides, app_handle)?;
+
+    remap_profiles_after_update(app_handle, std::slice::from_ref(previous), &guides.guides);
+
     Ok(())
 }
 
+/// Join a folder relative to the guides directory to `base`, refusing any path which could
+/// escape it. An empty folder stands for `base` itself.
+pub fn resolve_folder(base: &Path, folder: &str) -> Result<PathBuf, Error> {
+    let relative = Path::new(folder);
+
+    if !relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(Error::InvalidFolder(folder.to_string()));
+    }
+
+    Ok(base.join(relative))
+}
+
+pub fn is_local_guide(guide_id: u32) -> bool {
+    guide_id >= LOCAL_GUIDE_ID_START
+}
+
+/// Create an empty guide authored locally, stored in `folder` of the guides directory
+pub fn create_local_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    folder: String,
+    name: String,
+    description: Option<String>,
+    lang: GuideLang,
+    game_type: GameType,
+) -> Result<GuideWithSteps, Error> {
+    let folder = resolve_folder(&app_handle.path().app_guides_dir(), &folder)?;
+    let installed_guides = get_guides_from_handle(app_handle, "".to_string())?.guides;
+    let id = next_local_guide_id(used_guide_ids(app_handle, &installed_guides).into_iter())?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    info!("[Guides] creating local guide {} in {:?}", id, folder);
+
+    app_handle
+        .state::<GuidesLibrary>()
+        .record_app_change(&folder);
+    fs::create_dir_all(&folder).map_err(|err| Error::CreateGuidesDir(err.to_string()))?;
+
+    let guide = GuideWithSteps {
+        id,
+        name,
+        description,
+        status: Status::Private,
+        likes: 0,
+        dislikes: 0,
+        downloads: None,
+        deleted_at: None,
+        updated_at: Some(now),
+        lang,
+        game_type,
+        order: 0,
+        user: User {
+            id: 0,
+            name: String::new(),
+            is_admin: 0,
+            is_certified: 0,
+        },
+        web_description: None,
+        node_image: None,
+        steps: vec![],
+        folder: Some(folder),
+    };
+
+    write_guides(
+        &Guides {
+            guides: vec![guide.clone()],
+        },
+        app_handle,
+    )?;
+
+    Ok(guide)
+}
+
+/// Apply `edit` to a local guide and save it, moving the progress and notes of every profile
+/// when its steps are inserted, removed or reordered
+pub fn edit_local_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    edit: impl FnOnce(&mut GuideWithSteps) -> Result<(), Error>,
+) -> Result<GuideWithSteps, Error> {
+    if !is_local_guide(guide_id) {
+        return Err(Error::NotALocalGuide(guide_id));
+    }
+
+    let previous = get_installed_guide(app_handle, guide_id)?;
+    let mut guide = previous.clone();
+
+    edit(&mut guide)?;
+
+    guide.updated_at = Some(chrono::Utc::now().to_rfc3339());
+
+    debug!("[Guides] saving local guide {}", guide_id);
+
+    write_guide_and_remap(app_handle, &previous, guide.clone())?;
+
+    Ok(guide)
+}
+
+/// Quests of a guide with the steps where they are set up, started, progressed or completed,
+/// in the order they first appear
+pub fn extract_guide_quests(guide: &GuideWithSteps) -> Vec<QuestSummary> {
+    let mut quests: Vec<QuestSummary> = vec![];
+
+    for (step_index, step) in guide.steps.iter().enumerate() {
+        let document = scraper::Html::parse_document(&step.web_text);
+
+        let quest_selector = scraper::Selector::parse("[data-type='quest-block']").unwrap();
+
+        for element in document.select(&quest_selector) {
+            let quest_name = element.value().attr("questname");
+            let status = element.value().attr("status");
+            if let (Some(name), Some(status)) = (quest_name, status) {
+                let summary_quest_status = match status {
+                    "setup" => SummaryQuestStatus::Setup(step_index as u32 + 1),
+                    "start" => SummaryQuestStatus::Started(step_index as u32 + 1),
+                    "in_progress" => SummaryQuestStatus::InProgress(step_index as u32 + 1),
+                    "end" => SummaryQuestStatus::Completed(step_index as u32 + 1),
+                    _ => continue,
+                };
+
+                if let Some(quest) = quests.iter_mut().find(|q| q.name == name) {
+                    let in_vec_status = quest.statuses.iter().any(|s| s == &summary_quest_status);
+
+                    if !in_vec_status {
+                        quest.statuses.push(summary_quest_status);
+                    }
+                } else {
+                    let mut statuses = vec![];
+                    statuses.push(summary_quest_status);
+                    quests.push(QuestSummary {
+                        name: name.to_string(),
+                        statuses,
+                    });
+                }
+            }
+        }
+    }
+
+    quests
+}
+
+/// Indexes of the steps whose quest blocks all belong to completed quests, steps without any
+/// quest block are never skippable
+pub fn extract_skippable_steps(
+    guide: &GuideWithSteps,
+    is_completed: impl Fn(&str) -> bool,
+) -> Vec<u32> {
+    let quest_selector = scraper::Selector::parse("[data-type='quest-block']").unwrap();
+
+    guide
+        .steps
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| {
+            if !step.web_text.contains("quest-block") {
+                return false;
+            }
+
+            let document = scraper::Html::parse_fragment(&step.web_text);
+            let mut names = document
+                .select(&quest_selector)
+                .map(|element| element.value().attr("questname").unwrap_or_default())
+                .peekable();
+
+            names.peek().is_some() && names.all(&is_completed)
+        })
+        .map(|(step_index, _)| step_index as u32)
+        .collect()
+}
+
+/// Number of steps the next step shortcut moves forward in the guide the active profile is
+/// following, which is the opened guide whose progress changed last. It jumps over the skippable
+/// steps when enabled in the conf.
+pub fn next_step_offset<R: Runtime>(app_handle: &AppHandle<R>) -> Result<u32, Error> {
+    let conf = crate::conf::get_conf(app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+
+    if !conf.skip_completed_steps {
+        return Ok(1);
+    }
+
+    let Some(profile) = conf.profiles.iter().find(|p| p.id == conf.profile_in_use) else {
+        return Ok(1);
+    };
+
+    let opened_guides =
+        read_recent_guides_file(&app_handle.path().app_recent_guides_file(), &profile.id)?
+            .remove(&profile.id)
+            .unwrap_or_default()
+            .opened;
+
+    let Some(progress) = profile
+        .progresses
+        .iter()
+        .filter(|progress| opened_guides.contains(&progress.id))
+        .max_by_key(|progress| {
+            progress
+                .updated_at
+                .as_deref()
+                .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+        })
+    else {
+        return Ok(1);
+    };
+
+    let guide = get_installed_guide(app_handle, progress.id)?;
+    let skippable = extract_skippable_steps(&guide, |name| {
+        crate::conf::is_quest_completed(profile, name)
+    });
+
+    Ok(offset_to_next_step(
+        progress.current_step,
+        guide.steps.len() as u32,
+        &skippable,
+    ))
+}
+
+fn offset_to_next_step(current_step: u32, steps_count: u32, skippable: &[u32]) -> u32 {
+    let last_step = steps_count.saturating_sub(1);
+
+    if current_step >= last_step {
+        return 1;
+    }
+
+    (current_step + 1..last_step)
+        .find(|step| !skippable.contains(step))
+        .unwrap_or(last_step)
+        - current_step
+}
+
 fn add_or_replace_guide(guides: &mut Guides, guide: GuideWithSteps) -> Result<(), Error> {
     let guide_ref = &guide;
 
@@ -600,44 +1085,151 @@ fn add_or_replace_guides(guides: &mut Guides, new_guides: Vec<GuideWithSteps>) {
     }
 }
 
-/// Download a guide by its ID and save it into the specified folder
+/// Request a chunk of the guides batch, retrying with an exponential backoff while the error is
+/// transient.
+async fn request_guides_chunk_with_retry(
+    ids: &[u32],
+    http_client: &reqwest::Client,
+) -> Result<Vec<GuideWithSteps>, Error> {
+    let mut attempt = 1;
+
+    loop {
+        match request_guides_chunk(ids, http_client).await {
+            Err(err) if attempt < BATCH_MAX_ATTEMPTS && is_transient_error(&err) => {
+                let delay = retry_delay(attempt);
+
+                warn!(
+                    "[Guides] guides batch attempt {} failed, retrying in {:?}: {}",
+                    attempt, delay, err
+                );
+
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+async fn request_guides_chunk(
+    ids: &[u32],
+    http_client: &reqwest::Client,
+) -> Result<Vec<GuideWithSteps>, Error> {
+    let joined_ids = ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let response = http_client
+        .get(format!(
+            "{}/v2/guides/batch?ids={}",
+            GANYMEDE_API, joined_ids
+        ))
+        .send()
+        .await
+        .map_err(|err| {
+            if err.is_connect() || err.is_timeout() {
+                Error::NetworkUnavailable
+            } else {
+                Error::RequestGuides(err.to_string())
+            }
+        })?;
+
+    let status = response.status();
+
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(Error::RequestGuides(status.to_string()));
+    }
+
+    let text = response
+        .text()
+        .await
+        .map_err(|err| Error::RequestGuidesContent(err.to_string()))?;
+
+    crate::json::from_str::<BatchGuideResponse>(text.as_str())
+        .map(|batch| batch.data)
+        .map_err(Error::GuidesMalformed)
+}
+
+fn is_transient_error(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::NetworkUnavailable | Error::RequestGuides(_) | Error::RequestGuidesContent(_)
+    )
+}
+
+/// Delay before the next attempt, doubled after each failed attempt.
+fn retry_delay(attempt: u32) -> Duration {
+    BATCH_RETRY_BASE_DELAY * 2u32.pow(attempt - 1)
+}
+
+/// Download a guide by its ID and save it into the specified folder, the version it replaced is
+/// returned to remap the profiles once written.
 async fn download_guide_by_id<R: Runtime>(
     app: &AppHandle<R>,
     guides: &mut Guides,
     guide_id: u32,
     folder: String,
-) -> Result<(), Error> {
+) -> Result<Option<GuideWithSteps>, Error> {
     let http_client = app.state::<reqwest::Client>();
     let mut guide = get_guide_from_server(guide_id, &http_client).await?;
 
-    guide.folder = Some(app.path().app_guides_dir().join(folder.clone()));
+    guide.folder = Some(resolve_folder(&app.path().app_guides_dir(), &folder)?);
 
     debug!(
         "[Guides] download_guide_by_id: {} in {:?} (via {})",
         guide_id, guide.folder, folder
     );
 
+    let previous = guides.guides.iter().find(|g| g.id == guide_id).cloned();
+
+    if let Some(previous) = &previous {
+        if let Err(err) = snapshot_replaced_guide(app, previous, &guide) {
+            warn!(
+                "[Guides] failed to save previous version of guide {}: {}",
+                guide_id, err
+            );
+        }
+    }
+
     add_or_replace_guide(guides, guide)?;
 
-    Ok(())
+    Ok(previous)
 }
 
 async fn download_guides_by_ids<R: Runtime>(
     app: &AppHandle<R>,
     guides: &mut Guides,
     guide_ids: Vec<u32>,
-) -> Result<(), Vec<Error>> {
+) -> Result<(), Vec<(u32, Error)>> {
     let http_client = app.state::<reqwest::Client>();
-    let results = get_guides_from_server(guide_ids, &http_client).await;
+    let trigger = GuidesEventTrigger::new(app.clone());
+    let results = get_guides_from_server(guide_ids.clone(), &http_client, |done, total| {
+        if let Err(err) = trigger.guides_download_progress(GuidesDownloadProgress { done, total }) {
+            warn!(
+                "[Guides] failed to emit guides_download_progress event: {:?}",
+                err
+            );
+        }
+    })
+    .await;
 
     let mut downloaded_guides = vec![];
     let mut errors = vec![];
 
-    for result in results {
+    for (id, result) in guide_ids.into_iter().zip(results) {
         match result {
             Ok(mut guide) => {
                 if let Some(existing_guide) = guides.guides.iter().find(|g| g.id == guide.id) {
                     guide.folder = existing_guide.folder.clone();
+
+                    if let Err(err) = snapshot_replaced_guide(app, existing_guide, &guide) {
+                        warn!(
+                            "[Guides] failed to save previous version of guide {}: {}",
+                            guide.id, err
+                        );
+                    }
                 }
 
                 debug!(
@@ -649,7 +1241,7 @@ async fn download_guides_by_ids<R: Runtime>(
             }
             Err(error) => {
                 warn!("[Guides] download_guides_by_ids::for error: {}", error);
-                errors.push(error);
+                errors.push((id, error));
             }
         }
     }
@@ -672,19 +1264,26 @@ fn register_guide_open<R: Runtime>(
 
     let recent_guides_path = app_handle.path().app_recent_guides_file();
 
+    let step = progress_step(&app_handle, &profile_id, guide_id);
     let mut recent_guides = read_recent_guides_file(&recent_guides_path, &profile_id)?;
     let profile_guides = recent_guides.entry(profile_id).or_default();
 
-    if !profile_guides.contains(&guide_id) {
-        profile_guides.push(guide_id);
-        if profile_guides.len() > MAX_RECENT_GUIDES {
-            let overflow = profile_guides.len() - MAX_RECENT_GUIDES;
-            profile_guides.drain(0..overflow);
-        }
-
-        write_recent_guides_file(&recent_guides_path, &recent_guides)?;
+    if !profile_guides.opened.contains(&guide_id) {
+        profile_guides.opened.push(guide_id);
     }
 
+    let now = chrono::Utc::now();
+    let mut entry = take_recent_guide(&mut profile_guides.history, guide_id);
+
+    // a session still in progress is ended, or dropped when a previous run left it open
+    end_session(&mut entry, now, app_handle.state::<AppStartedAt>().0);
+    entry.last_opened_at = Some(now.to_rfc3339());
+    entry.session_started_at = Some(now.to_rfc3339());
+    entry.step = step.unwrap_or(entry.step);
+    profile_guides.history.push(entry);
+
+    write_recent_guides_file(&recent_guides_path, &recent_guides)?;
+
     Ok(())
 }
 
@@ -697,16 +1296,75 @@ fn register_guide_close<R: Runtime>(
 
     let recent_guides_path = app_handle.path().app_recent_guides_file();
 
+    let step = progress_step(&app_handle, &profile_id, guide_id);
     let mut recent_guides = read_recent_guides_file(&recent_guides_path, &profile_id)?;
 
     if let Some(profile_guides) = recent_guides.get_mut(&profile_id) {
-        profile_guides.retain(|&id| id != guide_id);
+        profile_guides.opened.retain(|&id| id != guide_id);
+
+        if let Some(entry) = profile_guides
+            .history
+            .iter_mut()
+            .find(|entry| entry.id == guide_id)
+        {
+            end_session(
+                entry,
+                chrono::Utc::now(),
+                app_handle.state::<AppStartedAt>().0,
+            );
+            entry.step = step.unwrap_or(entry.step);
+        }
+
         write_recent_guides_file(&recent_guides_path, &recent_guides)?;
     }
 
     Ok(())
 }
 
+/// Most recently opened guide of a profile still installed, with its progress.
+fn get_last_session<R: Runtime>(
+    app_handle: AppHandle<R>,
+    profile_id: String,
+) -> Result<Option<GuideSession>, Error> {
+    debug!("[Guides] get_last_session for profile {profile_id}");
+
+    let recent_guides_path = app_handle.path().app_recent_guides_file();
+    let history = read_recent_guides_file(&recent_guides_path, &profile_id)?
+        .remove(&profile_id)
+        .unwrap_or_default()
+        .history;
+    let conf = crate::conf::get_conf(&app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+    let profile = conf
+        .profiles
+        .iter()
+        .find(|profile| profile.id == profile_id);
+
+    for entry in history.into_iter().rev() {
+        let Ok(guide) = get_installed_guide(&app_handle, entry.id) else {
+            continue;
+        };
+        let progress = profile.and_then(|profile| {
+            profile
+                .progresses
+                .iter()
+                .find(|progress| progress.id == entry.id)
+                .cloned()
+        });
+
+        return Ok(Some(GuideSession {
+            guide,
+            step: progress
+                .as_ref()
+                .map_or(entry.step, |progress| progress.current_step),
+            progress,
+            last_opened_at: entry.last_opened_at,
+            time_spent_secs: entry.time_spent_secs,
+        }));
+    }
+
+    Ok(None)
+}
+
 fn get_recent_guides<R: Runtime>(
     app_handle: AppHandle<R>,
     profile_id: String,
@@ -722,7 +1380,10 @@ fn get_recent_guides<R: Runtime>(
         .map(|guide| guide.id)
         .collect::<HashSet<_>>();
 
-    let mut profile_guides = recent_guides.get(&profile_id).cloned().unwrap_or_default();
+    let mut profile_guides = recent_guides
+        .get(&profile_id)
+        .map(|profile_guides| profile_guides.opened.clone())
+        .unwrap_or_default();
 
     // remove any guide IDs that are no longer in the system since the last session
     profile_guides.retain(|id| guide_ids_in_system.contains(id));
@@ -743,7 +1404,7 @@ fn set_recent_guides<R: Runtime>(
     let recent_guides_path = app_handle.path().app_recent_guides_file();
     let mut recent_guides = read_recent_guides_file(&recent_guides_path, &profile_id)?;
 
-    recent_guides.insert(profile_id, sanitize_recent_guides(guide_ids));
+    recent_guides.entry(profile_id).or_default().opened = sanitize_recent_guides(guide_ids);
     write_recent_guides_file(&recent_guides_path, &recent_guides)?;
 
     Ok(())
@@ -779,7 +1440,7 @@ fn write_recent_guides_file(
         recent_guides_path
     );
 
-    fs::write(recent_guides_path.as_path(), json)
+    crate::storage::write(recent_guides_path, json)
         .map_err(|err| Error::WriteRecentGuidesFile(err.to_string()))?;
 
     Ok(())
@@ -788,15 +1449,81 @@ fn write_recent_guides_file(
 fn sanitize_recent_guides_by_profile(recent_guides: &RecentGuides) -> RecentGuides {
     recent_guides
         .iter()
-        .map(|(profile_id, guide_ids)| {
+        .map(|(profile_id, profile_guides)| {
             (
                 profile_id.clone(),
-                sanitize_recent_guides(guide_ids.clone()),
+                ProfileRecentGuides {
+                    opened: sanitize_recent_guides(profile_guides.opened.clone()),
+                    history: sanitize_recent_history(profile_guides.history.clone()),
+                },
             )
         })
         .collect()
 }
 
+/// Keep the last entry of every guide and the most recent ones up to the limit.
+fn sanitize_recent_history(history: Vec<RecentGuide>) -> Vec<RecentGuide> {
+    let mut seen = HashSet::new();
+    let mut sanitized = history
+        .into_iter()
+        .rev()
+        .filter(|entry| seen.insert(entry.id))
+        .take(MAX_RECENT_GUIDES)
+        .collect::<Vec<_>>();
+
+    sanitized.reverse();
+
+    sanitized
+}
+
+fn take_recent_guide(history: &mut Vec<RecentGuide>, guide_id: u32) -> RecentGuide {
+    match history.iter().position(|entry| entry.id == guide_id) {
+        Some(index) => history.remove(index),
+        None => RecentGuide {
+            id: guide_id,
+            ..Default::default()
+        },
+    }
+}
+
+/// Add the time of the session in progress to the time spent on the guide. A session started
+/// before `app_started_at` is dropped, the app was closed without closing the guide.
+fn end_session(
+    entry: &mut RecentGuide,
+    now: chrono::DateTime<chrono::Utc>,
+    app_started_at: chrono::DateTime<chrono::Utc>,
+) {
+    let started_at = entry
+        .session_started_at
+        .take()
+        .and_then(|date| chrono::DateTime::parse_from_rfc3339(&date).ok())
+        .filter(|started_at| *started_at >= app_started_at);
+
+    if let Some(started_at) = started_at {
+        let secs = (now - started_at.to_utc())
+            .num_seconds()
+            .clamp(0, u32::MAX as i64) as u32;
+
+        entry.time_spent_secs = entry.time_spent_secs.saturating_add(secs);
+    }
+}
+
+fn progress_step<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    profile_id: &str,
+    guide_id: u32,
+) -> Option<u32> {
+    let conf = crate::conf::get_conf(app_handle).ok()?;
+
+    conf.profiles
+        .iter()
+        .find(|profile| profile.id == profile_id)?
+        .progresses
+        .iter()
+        .find(|progress| progress.id == guide_id)
+        .map(|progress| progress.current_step)
+}
+
 fn sanitize_recent_guides(guide_ids: Vec<u32>) -> Vec<u32> {
     let mut sanitized = Vec::with_capacity(guide_ids.len().min(MAX_RECENT_GUIDES));
     let mut seen = HashSet::with_capacity(guide_ids.len().min(MAX_RECENT_GUIDES));
@@ -815,34 +1542,119 @@ fn sanitize_recent_guides(guide_ids: Vec<u32>) -> Vec<u32> {
     sanitized
 }
 
+/// Ids of the installed guides and of the guides a progress refers to, the ids of deleted local
+/// guides are not given out again while some progress still uses them.
+pub(crate) fn used_guide_ids<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    installed_guides: &[GuideWithSteps],
+) -> Vec<u32> {
+    let progress_ids = crate::conf::get_conf(app_handle)
+        .map(|conf| {
+            conf.profiles
+                .into_iter()
+                .flat_map(|profile| profile.progresses)
+                .map(|progress| progress.id)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    installed_guides
+        .iter()
+        .map(|guide| guide.id)
+        .chain(progress_ids)
+        .collect()
+}
+
+pub(crate) fn next_local_guide_id(used_ids: impl Iterator<Item = u32>) -> Result<u32, Error> {
+    match used_ids.filter(|id| is_local_guide(*id)).max() {
+        Some(id) => id.checked_add(1).ok_or(Error::LocalGuideIdsExhausted),
+        None => Ok(LOCAL_GUIDE_ID_START),
+    }
+}
+
+fn check_step_index(guide: &GuideWithSteps, index: u32) -> Result<usize, Error> {
+    if (index as usize) < guide.steps.len() {
+        Ok(index as usize)
+    } else {
+        Err(Error::StepOutOfRange(index))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_guides_from_path, sanitize_recent_guides, MAX_RECENT_GUIDES};
+    use std::{path::Path, time::Duration};
+
+    use super::{
+        add_or_replace_guide, end_session, extract_skippable_steps, get_guides_from_path,
+        is_transient_error, next_local_guide_id, offset_to_next_step, read_recent_guides_file,
+        remap_profiles, resolve_folder, resolve_subfolder, retry_delay, sanitize_recent_guides,
+        step_mappings, Error, Guides, RecentGuide, LOCAL_GUIDE_ID_START, MAX_RECENT_GUIDES,
+    };
+    use crate::test_guides::{guide, step, step_at};
 
     #[test]
-    fn get_guides_from_path_skips_and_removes_malformed_files() {
+    fn get_guides_from_path_skips_and_quarantines_malformed_files() {
         use std::fs;
 
         let dir = tempfile::tempdir().unwrap();
+        let quarantine_dir = tempfile::tempdir().unwrap();
         let valid = r#"{"id":42,"name":"Valid","status":"public","likes":0,"dislikes":0,"lang":"fr","order":0,"user":{"id":1,"name":"u","is_admin":0,"is_certified":0},"steps":[]}"#;
 
         fs::write(dir.path().join("42.json"), valid).unwrap();
         fs::write(dir.path().join("2022.json"), "").unwrap();
 
-        let (guides, removed) = get_guides_from_path(&dir.path().to_path_buf()).unwrap();
+        let (guides, quarantined) =
+            get_guides_from_path(&dir.path().to_path_buf(), quarantine_dir.path()).unwrap();
 
         assert_eq!(guides.guides.len(), 1);
         assert_eq!(guides.guides[0].id, 42);
 
-        assert_eq!(removed.len(), 1);
-        assert_eq!(removed[0].id, Some(2022));
-        assert_eq!(removed[0].file_name, "2022.json");
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(quarantined[0].id, Some(2022));
+        assert_eq!(quarantined[0].file_name, "2022.json");
 
-        // the malformed file is deleted while the valid one is kept
+        // the malformed file is moved to the quarantine while the valid one is kept
         assert!(!dir.path().join("2022.json").exists());
+        assert!(quarantine_dir
+            .path()
+            .join(&quarantined[0].entry)
+            .join("2022.json")
+            .exists());
         assert!(dir.path().join("42.json").exists());
     }
 
+    #[test]
+    fn resolve_folder_rejects_paths_escaping_guides_dir() {
+        let guides_dir = Path::new("/config/guides");
+
+        assert_eq!(
+            resolve_folder(guides_dir, "dofus/quests").unwrap(),
+            guides_dir.join("dofus").join("quests")
+        );
+        assert_eq!(resolve_folder(guides_dir, "").unwrap(), guides_dir);
+        assert!(resolve_folder(guides_dir, "../conf").is_err());
+        assert!(resolve_folder(guides_dir, "dofus/../../conf").is_err());
+        assert!(resolve_folder(guides_dir, "/etc").is_err());
+        assert!(resolve_subfolder(guides_dir, "").is_err());
+    }
+
+    #[test]
+    fn next_local_guide_id_ignores_server_ids() {
+        assert_eq!(
+            next_local_guide_id([12, 1074].into_iter()).unwrap(),
+            LOCAL_GUIDE_ID_START
+        );
+        assert_eq!(
+            next_local_guide_id([12, LOCAL_GUIDE_ID_START + 4, LOCAL_GUIDE_ID_START].into_iter())
+                .unwrap(),
+            LOCAL_GUIDE_ID_START + 5
+        );
+        assert!(matches!(
+            next_local_guide_id([u32::MAX].into_iter()),
+            Err(Error::LocalGuideIdsExhausted)
+        ));
+    }
+
     #[test]
     fn sanitize_recent_guides_removes_duplicates_preserving_order() {
         let guide_ids = vec![3, 1, 3, 2, 1, 4];
@@ -850,6 +1662,57 @@ mod tests {
         assert_eq!(sanitize_recent_guides(guide_ids), vec![3, 1, 2, 4]);
     }
 
+    #[test]
+    fn read_recent_guides_file_migrates_ids_to_history() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recent_guides.json");
+
+        std::fs::write(&path, r#"{"p1":[3,1]}"#).unwrap();
+
+        let recent_guides = read_recent_guides_file(&path, "p1").unwrap();
+
+        assert_eq!(recent_guides["p1"].opened, vec![3, 1]);
+        assert_eq!(recent_guides["p1"].history[1].id, 1);
+        assert!(recent_guides["p1"].history[1].last_opened_at.is_none());
+        assert_eq!(
+            read_recent_guides_file(&path, "p1").unwrap()["p1"].opened,
+            vec![3, 1]
+        );
+    }
+
+    #[test]
+    fn end_session_adds_the_session_time_of_this_run() {
+        let date = |date: &str| date.parse::<chrono::DateTime<chrono::Utc>>().unwrap();
+        let session = |started_at: &str| RecentGuide {
+            id: 1,
+            time_spent_secs: 10,
+            session_started_at: Some(started_at.to_string()),
+            ..Default::default()
+        };
+
+        let mut entry = session("2024-01-01T10:00:00+00:00");
+
+        end_session(
+            &mut entry,
+            date("2024-01-01T10:01:30Z"),
+            date("2024-01-01T09:00:00Z"),
+        );
+
+        assert_eq!(entry.time_spent_secs, 100);
+        assert!(entry.session_started_at.is_none());
+
+        let mut stale = session("2024-01-01T10:00:00+00:00");
+
+        end_session(
+            &mut stale,
+            date("2024-01-03T10:00:00Z"),
+            date("2024-01-03T09:00:00Z"),
+        );
+
+        assert_eq!(stale.time_spent_secs, 10);
+        assert!(stale.session_started_at.is_none());
+    }
+
     #[test]
     fn sanitize_recent_guides_caps_to_limit() {
         let guide_ids = (1..=(MAX_RECENT_GUIDES as u32 + 10)).collect::<Vec<_>>();
@@ -859,28 +1722,110 @@ mod tests {
         assert_eq!(sanitized.first(), Some(&11));
         assert_eq!(sanitized.last(), Some(&(MAX_RECENT_GUIDES as u32 + 10)));
     }
+
+    #[test]
+    fn retry_delay_doubles_after_each_attempt() {
+        assert_eq!(retry_delay(1), Duration::from_millis(500));
+        assert_eq!(retry_delay(3), Duration::from_secs(2));
+        assert!(is_transient_error(&Error::NetworkUnavailable));
+        assert!(!is_transient_error(&Error::GuideNotFound(1)));
+    }
+
+    #[test]
+    fn skippable_steps_only_hold_completed_quests() {
+        let guide = guide(1)
+            .steps(vec![
+                step("<p>no quest</p>"),
+                step("<div data-type='quest-block' questname='Dopple' status='start'></div>"),
+                step("<div data-type='quest-block' questname='Dopple' status='end'></div><div data-type='quest-block' questname='Kwak' status='start'></div>"),
+                step("<div data-type='quest-block' questname='Dopple' status='end'></div>"),
+                step("<p>last</p>"),
+            ])
+            .build();
+
+        let skippable = extract_skippable_steps(&guide, |name| name == "Dopple");
+
+        assert_eq!(skippable, vec![1, 3]);
+        assert_eq!(offset_to_next_step(0, 5, &skippable), 2);
+        assert_eq!(offset_to_next_step(2, 5, &skippable), 2);
+        assert_eq!(offset_to_next_step(4, 5, &skippable), 1);
+        assert_eq!(offset_to_next_step(0, 3, &[1]), 2);
+    }
+
+    #[test]
+    fn redownloaded_guide_remaps_profile_progress() {
+        let mut guides = Guides {
+            guides: vec![guide(1)
+                .steps(vec![step_at("a", 0, 0), step_at("b", 1, 1)])
+                .build()],
+        };
+        let previous = guides.guides.clone();
+
+        add_or_replace_guide(
+            &mut guides,
+            guide(1)
+                .steps(vec![
+                    step_at("inserted", 5, 5),
+                    step_at("a", 0, 0),
+                    step_at("b", 1, 1),
+                ])
+                .build(),
+        )
+        .unwrap();
+
+        let mut profiles = vec![crate::conf::Profile {
+            id: "p1".to_string(),
+            name: "p1".to_string(),
+            level: 1,
+            progresses: vec![crate::conf::Progress {
+                id: 1,
+                current_step: 1,
+                steps: Default::default(),
+                updated_at: None,
+            }],
+            server_id: None,
+            completed_quests: vec![],
+        }];
+
+        remap_profiles(&mut profiles, &step_mappings(&previous, &guides.guides));
+
+        assert_eq!(profiles[0].progresses[0].current_step, 2);
+        assert!(step_mappings(&guides.guides, &guides.guides).is_empty());
+    }
 }
 
 fn read_recent_guides_file(
     recent_guides_path: &PathBuf,
     profile_id: &str,
 ) -> Result<RecentGuides, Error> {
-    if recent_guides_path.exists() {
-        debug!("[Guides] reading recent guides file");
+    let file = crate::storage::read_to_string(recent_guides_path).map_err(|err| match err {
+        ReadError::Io(err) => Error::ReadRecentGuidesFile(err),
+        ReadError::Malformed(err) => Error::RecentGuidesFileMalformed(err.to_string()),
+    })?;
 
-        let file = fs::read_to_string(recent_guides_path)
-            .map_err(|err| Error::ReadRecentGuidesFile(err.to_string()))?;
+    if let Some(file) = file {
+        debug!("[Guides] reading recent guides file");
 
-        // Try new format: HashMap<String, Vec<u32>>
         if let Ok(recent_guides) = crate::json::from_str::<RecentGuides>(&file) {
             return Ok(recent_guides);
         }
 
+        // Fallback: ids by profile HashMap<String, Vec<u32>> -> migrate without timestamps
+        if let Ok(ids_by_profile) = crate::json::from_str::<HashMap<String, Vec<u32>>>(&file) {
+            debug!("[Guides] ids format detected, migrating to the history format");
+            let migrated = ids_by_profile
+                .into_iter()
+                .map(|(profile_id, guide_ids)| (profile_id, migrate_recent_guide_ids(guide_ids)))
+                .collect();
+            write_recent_guides_file(recent_guides_path, &migrated)?;
+            return Ok(migrated);
+        }
+
         // Fallback: old format Vec<u32> -> migrate to current profile and persist
         if let Ok(old_guides) = crate::json::from_str::<Vec<u32>>(&file) {
             debug!("[Guides] old format detected, migrating to profile {profile_id}");
             let mut migrated = HashMap::new();
-            migrated.insert(profile_id.to_string(), old_guides);
+            migrated.insert(profile_id.to_string(), migrate_recent_guide_ids(old_guides));
             write_recent_guides_file(recent_guides_path, &migrated)?;
             return Ok(migrated);
         }
@@ -895,6 +1840,19 @@ fn read_recent_guides_file(
     }
 }
 
+fn migrate_recent_guide_ids(guide_ids: Vec<u32>) -> ProfileRecentGuides {
+    ProfileRecentGuides {
+        history: guide_ids
+            .iter()
+            .map(|id| RecentGuide {
+                id: *id,
+                ..Default::default()
+            })
+            .collect(),
+        opened: guide_ids,
+    }
+}
+
 fn fetch_guides_from_server<R: Runtime>(
     app_handle: &AppHandle<R>,
     status: Option<Status>,
@@ -925,28 +1883,9 @@ fn fetch_guides_from_server<R: Runtime>(
             });
         }
 
-        let http_client = app_handle.state::<reqwest::Client>();
-
-        let url = if let Some(status) = status {
-            format!("{}/v2/guides?status={}", GANYMEDE_API, status.to_str())
-        } else {
-            format!("{}/v2/guides", GANYMEDE_API)
-        };
-
-        let res = http_client.get(url).send().await.map_err(|err| {
-            if err.is_connect() || err.is_timeout() {
-                Error::NetworkUnavailable
-            } else {
-                Error::RequestGuides(err.to_string())
-            }
-        })?;
-
-        let text = res
-            .text()
+        get_catalog(&app_handle, status)
             .await
-            .map_err(|err| Error::RequestGuidesContent(err.to_string()))?;
-
-        crate::json::from_str::<Vec<Guide>>(text.as_str()).map_err(Error::GuidesMalformed)
+            .map(|catalog| catalog.guides)
     }
 }
 
@@ -979,9 +1918,10 @@ fn download_and_save_guide<R: Runtime>(
 
         let mut guides = get_guides_from_handle(&app, folder.clone())?;
 
-        download_guide_by_id(&app, &mut guides, guide_id, folder).await?;
+        let previous = download_guide_by_id(&app, &mut guides, guide_id, folder).await?;
 
         write_guides(&guides, &app)?;
+        remap_profiles_after_update(&app, previous.as_slice(), &guides.guides);
 
         Ok(guides)
     }
@@ -1028,46 +1968,7 @@ fn generate_guide_summary<R: Runtime>(
 
         match guide {
             Some(guide) => {
-                let mut quests: Vec<QuestSummary> = vec![];
-
-                for (step_index, step) in guide.steps.iter().enumerate() {
-                    let document = scraper::Html::parse_document(&step.web_text);
-
-                    let quest_selector =
-                        scraper::Selector::parse("[data-type='quest-block']").unwrap();
-
-                    for element in document.select(&quest_selector) {
-                        let quest_name = element.value().attr("questname");
-                        let status = element.value().attr("status");
-                        if let (Some(name), Some(status)) = (quest_name, status) {
-                            let summary_quest_status = match status {
-                                "setup" => SummaryQuestStatus::Setup(step_index as u32 + 1),
-                                "start" => SummaryQuestStatus::Started(step_index as u32 + 1),
-                                "in_progress" => {
-                                    SummaryQuestStatus::InProgress(step_index as u32 + 1)
-                                }
-                                "end" => SummaryQuestStatus::Completed(step_index as u32 + 1),
-                                _ => continue,
-                            };
-
-                            if let Some(quest) = quests.iter_mut().find(|q| q.name == name) {
-                                let in_vec_status =
-                                    quest.statuses.iter().any(|s| s == &summary_quest_status);
-
-                                if !in_vec_status {
-                                    quest.statuses.push(summary_quest_status);
-                                }
-                            } else {
-                                let mut statuses = vec![];
-                                statuses.push(summary_quest_status);
-                                quests.push(QuestSummary {
-                                    name: name.to_string(),
-                                    statuses,
-                                });
-                            }
-                        }
-                    }
-                }
+                let mut quests = extract_guide_quests(guide);
 
                 let duration = start.elapsed();
 
@@ -1136,74 +2037,192 @@ fn generate_guide_summary<R: Runtime>(
     }
 }
 
+/// Update the server guides following their update policy: the guides updated automatically are
+/// downloaded, the available updates of the notify-only guides are reported and the others are
+/// skipped.
 async fn update_all_guides_batch<R: Runtime>(
     app_handle: &AppHandle<R>,
 ) -> Result<HashMap<u32, UpdateAllAtOnceResult>, Error> {
     info!("[Guides] update_all_guides_batch");
 
+    let guides = get_guides_from_handle(app_handle, "".to_string())?;
+    // a broken policies file must not block the updates, every guide falls back to Auto
+    let policies = get_update_policies(app_handle).unwrap_or_else(|err| {
+        warn!(
+            "[Guides] cannot read the update policies, using the defaults: {}",
+            err
+        );
+        GuideUpdatePolicies::default()
+    });
+    let mut auto_ids = vec![];
+    let mut notify_ids = vec![];
+    let mut skipped = HashMap::new();
+
+    for guide in guides.guides.iter().filter(|g| !is_local_guide(g.id)) {
+        match policies.policy(guide.id) {
+            UpdatePolicy::Auto => auto_ids.push(guide.id),
+            policy => {
+                if policy == UpdatePolicy::NotifyOnly {
+                    notify_ids.push(guide.id);
+                }
+
+                skipped.insert(guide.id, UpdateAllAtOnceResult::Skipped { policy });
+            }
+        }
+    }
+
+    let mut results = update_guides_by_ids(app_handle, auto_ids).await?;
+
+    if !notify_ids.is_empty() {
+        notify_available_updates(app_handle, &guides.guides, &notify_ids).await;
+    }
+
+    results.extend(skipped);
+
+    Ok(results)
+}
+
+/// Download the given server guides whatever their update policy, then remap the profiles.
+async fn update_guides_by_ids<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_ids: Vec<u32>,
+) -> Result<HashMap<u32, UpdateAllAtOnceResult>, Error> {
+    if guide_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
     let mut guides = get_guides_from_handle(app_handle, "".to_string())?;
-    let guide_ids: Vec<u32> = guides.guides.iter().map(|g| g.id).collect();
+    let previous_guides = guides.guides.clone();
 
     let result = download_guides_by_ids(app_handle, &mut guides, guide_ids.clone()).await;
 
     let mut results = HashMap::new();
 
-    match result {
-        Ok(()) => {
-            for id in guide_ids {
-                results.insert(id, UpdateAllAtOnceResult::Success);
+    // only the guides of the failed chunks are reported, the network errors as offline
+    if let Err(errors) = result {
+        for (id, error) in errors {
+            let result = match error {
+                Error::NetworkUnavailable => UpdateAllAtOnceResult::Offline,
+                error => UpdateAllAtOnceResult::Failure {
+                    message: error.to_string(),
+                },
+            };
+
+            results.insert(id, result);
+        }
+    }
+
+    for id in guide_ids {
+        results.entry(id).or_insert(UpdateAllAtOnceResult::Success);
+    }
+
+    write_guides(&guides, app_handle)?;
+
+    remap_profiles_after_update(app_handle, &previous_guides, &guides.guides);
+    publish_changelog(app_handle, &previous_guides, &guides.guides);
+
+    Ok(results)
+}
+
+/// Move the progress, checked boxes and step notes of every profile to the new step indices of
+/// the guides whose steps were inserted, removed or reordered by an update.
+fn remap_profiles_after_update<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    previous_guides: &[GuideWithSteps],
+    updated_guides: &[GuideWithSteps],
+) {
+    let mappings = step_mappings(previous_guides, updated_guides);
+
+    if mappings.is_empty() {
+        return;
+    }
+
+    info!(
+        "[Guides] remapping progress of {} updated guides",
+        mappings.len()
+    );
+
+    match crate::conf::get_conf(app_handle) {
+        Ok(mut conf) => {
+            remap_profiles(&mut conf.profiles, &mappings);
+
+            if let Err(err) = crate::conf::save_conf(&mut conf, app_handle) {
+                warn!("[Guides] failed to save remapped progress: {:?}", err);
             }
         }
-        Err(errors) => {
-            // If all errors are network errors, treat as success (offline mode)
-            let all_network_errors = errors
-                .iter()
-                .all(|e| matches!(e, Error::NetworkUnavailable));
+        Err(err) => warn!("[Guides] failed to get conf to remap progress: {:?}", err),
+    }
 
-            if all_network_errors {
-                for id in guide_ids {
-                    results.insert(id, UpdateAllAtOnceResult::Offline);
-                }
-            } else {
-                let mut error_ids: Vec<u32> = vec![];
-
-                for error in errors {
-                    match error {
-                        Error::GuideNotFound(id) => {
-                            results.insert(
-                                id,
-                                UpdateAllAtOnceResult::Failure {
-                                    message: error.to_string(),
-                                },
-                            );
-                            error_ids.push(id);
-                        }
-                        _ => {
-                            for id in &guide_ids {
-                                if !error_ids.contains(id) {
-                                    results
-                                        .entry(*id)
-                                        .or_insert(UpdateAllAtOnceResult::Failure {
-                                            message: error.to_string(),
-                                        });
-                                }
-                            }
-                        }
+    match crate::step_notes::get_step_notes(app_handle) {
+        Ok(mut notes) => {
+            for profile_notes in notes.profiles.values_mut() {
+                for (guide_id, guide_notes) in profile_notes.guides.iter_mut() {
+                    if let Some((_, _, mapping)) = mappings.get(guide_id) {
+                        remap_step_notes(guide_notes, mapping);
                     }
                 }
+            }
 
-                for id in guide_ids {
-                    if !results.contains_key(&id) {
-                        results.insert(id, UpdateAllAtOnceResult::Success);
-                    }
-                }
+            if let Err(err) = crate::step_notes::save_step_notes(&notes, app_handle) {
+                warn!("[Guides] failed to save remapped step notes: {:?}", err);
             }
         }
+        Err(err) => warn!("[Guides] failed to get step notes to remap: {:?}", err),
     }
 
-    write_guides(&guides, app_handle)?;
+    let remapped = mappings
+        .iter()
+        .filter_map(|(guide_id, (_, _, mapping))| {
+            let unmapped_steps = mapping.unmapped();
 
-    Ok(results)
+            (!unmapped_steps.is_empty()).then_some(RemappedGuideSteps {
+                guide_id: *guide_id,
+                unmapped_steps,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if remapped.is_empty() {
+        return;
+    }
+
+    let trigger = GuidesEventTrigger::new(app_handle.clone());
+
+    if let Err(err) = trigger.guide_steps_remapped(remapped) {
+        warn!(
+            "[Guides] failed to emit guide_steps_remapped event: {:?}",
+            err
+        );
+    }
+}
+
+/// Step mappings of the updated guides whose steps changed, with their previous version.
+fn step_mappings<'a>(
+    previous_guides: &'a [GuideWithSteps],
+    updated_guides: &'a [GuideWithSteps],
+) -> HashMap<u32, (&'a GuideWithSteps, &'a GuideWithSteps, StepMapping)> {
+    updated_guides
+        .iter()
+        .filter_map(|guide| {
+            let previous = previous_guides.iter().find(|g| g.id == guide.id)?;
+            let mapping = StepMapping::between(&previous.steps, &guide.steps);
+
+            (!mapping.is_identity()).then_some((guide.id, (previous, guide, mapping)))
+        })
+        .collect()
+}
+
+fn remap_profiles(
+    profiles: &mut [crate::conf::Profile],
+    mappings: &HashMap<u32, (&GuideWithSteps, &GuideWithSteps, StepMapping)>,
+) {
+    for profile in profiles {
+        for progress in &mut profile.progresses {
+            if let Some((previous, guide, mapping)) = mappings.get(&progress.id) {
+                remap_progress(progress, mapping, &previous.steps, &guide.steps);
+            }
+        }
+    }
 }
 
 fn check_guides_need_update<R: Runtime>(
@@ -1220,17 +2239,13 @@ fn check_guides_need_update<R: Runtime>(
         };
 
         let guides_in_system = get_guides_from_handle(&app_handle, "".to_string())?;
+        let policies = get_update_policies(&app_handle)?;
 
-        for guide in guides_in_server {
-            if let Some(guide_in_system) = guides_in_system.guides.iter().find(|g| g.id == guide.id)
-            {
-                if guide.updated_at != guide_in_system.updated_at {
-                    return Ok(true);
-                }
-            }
-        }
-
-        Ok(false)
+        Ok(
+            available_updates(&guides_in_system.guides, &guides_in_server)
+                .iter()
+                .any(|update| policies.policy(update.id) != UpdatePolicy::Frozen),
+        )
     }
 }
 
@@ -1244,31 +2259,37 @@ fn delete_guides_and_folders<R: Runtime>(
     );
 
     let guides_dir = app_handle.path().app_guides_dir();
+    let library = app_handle.state::<GuidesLibrary>();
 
     for guide_or_folder_to_delete in guides_or_folders_to_delete {
-        let mut path = guides_dir.clone();
-
-        match guide_or_folder_to_delete {
+        let path = match guide_or_folder_to_delete {
             GuideOrFolderToDelete::Guide { id, folder } => {
-                if let Some(folder) = folder {
-                    path = path.join(folder);
-                }
+                let path = resolve_folder(&guides_dir, &folder.unwrap_or_default())?
+                    .join(format!("{}.json", id));
+
+                library.remove_guide(id);
 
-                path = path.join(format!("{}.json", id));
+                path
             }
             GuideOrFolderToDelete::Folder { folder } => {
-                path = path.join(folder);
+                let path = resolve_subfolder(&guides_dir, &folder)?;
+
+                library.remove_folder(&path);
+
+                path
             }
-        }
+        };
 
         info!("[Guides] deleting the following path: {:?}", path);
 
+        library.record_app_change(&path);
+
         if path.exists() {
             if path.is_dir() {
                 fs::remove_dir_all(path)
                     .map_err(|err| Error::DeleteGuideFolderInSystem(err.to_string()))?;
             } else {
-                fs::remove_file(path)
+                crate::storage::remove(&path)
                     .map_err(|err| Error::DeleteGuideFileInSystem(err.to_string()))?;
             }
         }
@@ -1277,38 +2298,222 @@ fn delete_guides_and_folders<R: Runtime>(
     Ok(())
 }
 
-fn check_guide_exists<R: Runtime>(
+/// Resolve a folder which must not be the guides directory itself
+fn resolve_subfolder(guides_dir: &Path, folder: &str) -> Result<PathBuf, Error> {
+    let path = resolve_folder(guides_dir, folder)?;
+
+    if path == guides_dir {
+        return Err(Error::InvalidFolder(folder.to_string()));
+    }
+
+    Ok(path)
+}
+
+fn create_folder<R: Runtime>(app_handle: &AppHandle<R>, folder: String) -> Result<(), Error> {
+    info!("[Guides] create_folder: {}", folder);
+
+    let path = resolve_subfolder(&app_handle.path().app_guides_dir(), &folder)?;
+
+    if path.exists() {
+        return Err(Error::FolderAlreadyExists(folder));
+    }
+
+    app_handle.state::<GuidesLibrary>().record_app_change(&path);
+    fs::create_dir_all(path).map_err(|err| Error::CreateFolder(err.to_string()))
+}
+
+fn rename_folder<R: Runtime>(
     app_handle: &AppHandle<R>,
-    guide_id: u32,
-) -> impl std::future::Future<Output = Result<bool, Error>> + Send {
-    let app_handle = app_handle.clone();
-    async move {
-        debug!("[Guides] Checking if guide {} exists", guide_id);
+    folder: String,
+    name: String,
+) -> Result<(), Error> {
+    info!("[Guides] rename_folder: {} to {}", folder, name);
 
-        let guides = get_guides_from_handle(&app_handle, "".to_string())?;
-        let exists = guides.guides.iter().any(|g| g.id == guide_id);
+    let guides_dir = app_handle.path().app_guides_dir();
+    let path = resolve_subfolder(&guides_dir, &folder)?;
 
-        debug!("[Guides] Guide {} exists: {}", guide_id, exists);
-        Ok(exists)
+    // the new name is a single folder name, not a path
+    if Path::new(&name).components().count() != 1 {
+        return Err(Error::InvalidFolder(name));
     }
+
+    let parent = path.parent().unwrap_or(&guides_dir);
+    let destination = resolve_folder(parent, &name)?;
+
+    move_folder_in_system(app_handle, &folder, &path, &destination)
 }
 
-// ================================================================================================
-// TauRPC API Trait & Implementation
-// ================================================================================================
+/// Move a folder with its guides and subfolders into `destination`, empty for the root
+fn move_folder<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    folder: String,
+    destination: String,
+) -> Result<(), Error> {
+    info!("[Guides] move_folder: {} to {:?}", folder, destination);
 
-#[taurpc::procedures(path = "guides", event_trigger = GuidesEventTrigger, export_to = "../src/ipc/bindings.ts")]
-pub trait GuidesApi {
-    #[taurpc(alias = "getFlatGuides")]
-    async fn get_flat_guides<R: Runtime>(
-        app_handle: AppHandle<R>,
-        folder: String,
-    ) -> Result<Vec<GuideWithSteps>, Error>;
-    #[taurpc(alias = "getGuides")]
-    async fn get_guides<R: Runtime>(
+    let guides_dir = app_handle.path().app_guides_dir();
+    let path = resolve_subfolder(&guides_dir, &folder)?;
+    let destination_dir = resolve_folder(&guides_dir, &destination)?;
+
+    if destination_dir.starts_with(&path) {
+        return Err(Error::InvalidFolder(destination));
+    }
+
+    if !destination_dir.is_dir() {
+        return Err(Error::FolderNotFound(destination));
+    }
+
+    let name = path
+        .file_name()
+        .ok_or_else(|| Error::InvalidFolder(folder.clone()))?;
+
+    move_folder_in_system(app_handle, &folder, &path, &destination_dir.join(name))
+}
+
+fn move_folder_in_system<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    folder: &str,
+    path: &Path,
+    destination: &Path,
+) -> Result<(), Error> {
+    if !path.is_dir() {
+        return Err(Error::FolderNotFound(folder.to_string()));
+    }
+
+    if destination.exists() {
+        return Err(Error::FolderAlreadyExists(
+            destination.to_string_lossy().to_string(),
+        ));
+    }
+
+    let library = app_handle.state::<GuidesLibrary>();
+
+    library.record_app_change(path);
+    library.record_app_change(destination);
+    fs::rename(path, destination).map_err(|err| Error::MoveInSystem(err.to_string()))?;
+
+    // the folders of the guides changed, they are loaded again on the next read
+    library.invalidate();
+
+    Ok(())
+}
+
+/// Move an installed guide into `destination`, empty for the root
+fn move_guide<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    destination: String,
+) -> Result<GuideWithSteps, Error> {
+    info!("[Guides] move_guide: {} to {:?}", guide_id, destination);
+
+    let destination_dir = resolve_folder(&app_handle.path().app_guides_dir(), &destination)?;
+
+    if !destination_dir.is_dir() {
+        return Err(Error::FolderNotFound(destination));
+    }
+
+    let mut guide = get_installed_guide(app_handle, guide_id)?;
+    let file_name = format!("{}.json", guide_id);
+    let source = guide
+        .folder
+        .as_ref()
+        .ok_or(Error::GetGuideInSystem(guide_id))?
+        .join(&file_name);
+
+    let library = app_handle.state::<GuidesLibrary>();
+    let destination = destination_dir.join(&file_name);
+
+    library.record_app_change(&source);
+    library.record_app_change(&destination);
+    crate::storage::rename(&source, &destination)
+        .map_err(|err| Error::MoveInSystem(err.to_string()))?;
+
+    guide.folder = Some(destination_dir);
+
+    library.upsert(&guide);
+
+    Ok(guide)
+}
+
+fn check_guide_exists<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> impl std::future::Future<Output = Result<bool, Error>> + Send {
+    let app_handle = app_handle.clone();
+    async move {
+        debug!("[Guides] Checking if guide {} exists", guide_id);
+
+        let guides = get_guides_from_handle(&app_handle, "".to_string())?;
+        let exists = guides.guides.iter().any(|g| g.id == guide_id);
+
+        debug!("[Guides] Guide {} exists: {}", guide_id, exists);
+        Ok(exists)
+    }
+}
+
+fn search_guides<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    query: String,
+) -> Result<Vec<GuideSearchResult>, Error> {
+    debug!("[Guides] search_guides: {}", query);
+
+    let library = app_handle.state::<GuidesLibrary>();
+
+    if !library.is_loaded() {
+        get_guides_from_handle(app_handle, "".to_string())?;
+    }
+
+    Ok(app_handle
+        .state::<GuidesSearchIndex>()
+        .search(&library, &query, MAX_SEARCH_RESULTS))
+}
+
+/// Load the guides library the quest index is built from, and return the progresses of the active
+/// profile to tell which quests it completed
+fn load_library_and_progresses<R: Runtime>(
+    app_handle: &AppHandle<R>,
+) -> Result<Vec<crate::conf::Progress>, Error> {
+    if !app_handle.state::<GuidesLibrary>().is_loaded() {
+        get_guides_from_handle(app_handle, "".to_string())?;
+    }
+
+    let conf = crate::conf::get_conf(app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+    let progresses = conf
+        .profiles
+        .into_iter()
+        .find(|profile| profile.id == conf.profile_in_use)
+        .map(|profile| profile.progresses)
+        .unwrap_or_default();
+
+    Ok(progresses)
+}
+
+// ================================================================================================
+// TauRPC API Trait & Implementation
+// ================================================================================================
+
+#[taurpc::procedures(path = "guides", event_trigger = GuidesEventTrigger, export_to = "../src/ipc/bindings.ts")]
+pub trait GuidesApi {
+    #[taurpc(alias = "getFlatGuides")]
+    async fn get_flat_guides<R: Runtime>(
+        app_handle: AppHandle<R>,
+        folder: String,
+    ) -> Result<Vec<GuideWithSteps>, Error>;
+    #[taurpc(alias = "getGuides")]
+    async fn get_guides<R: Runtime>(
         app_handle: AppHandle<R>,
         folder: Option<String>,
     ) -> Result<Vec<GuidesOrFolder>, Error>;
+    #[taurpc(alias = "getGuideHeaders")]
+    async fn get_guide_headers<R: Runtime>(
+        app_handle: AppHandle<R>,
+        folder: Option<String>,
+    ) -> Result<Vec<GuideHeaderOrFolder>, Error>;
+    #[taurpc(alias = "getGuide")]
+    async fn get_guide<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<GuideWithSteps, Error>;
     #[taurpc(alias = "getGuideFromServer")]
     async fn get_guide_from_server<R: Runtime>(
         app_handle: AppHandle<R>,
@@ -1319,6 +2524,18 @@ pub trait GuidesApi {
         app_handle: AppHandle<R>,
         status: Option<Status>,
     ) -> Result<Vec<Guide>, Error>;
+    #[taurpc(alias = "getCatalog")]
+    async fn get_catalog<R: Runtime>(
+        app_handle: AppHandle<R>,
+        status: Option<Status>,
+    ) -> Result<GuideCatalog, Error>;
+    #[taurpc(alias = "queryGuides")]
+    async fn query_guides<R: Runtime>(
+        app_handle: AppHandle<R>,
+        query: GuideQuery,
+    ) -> Result<GuidesPage, Error>;
+    #[taurpc(alias = "markCatalogVisited")]
+    async fn mark_catalog_visited<R: Runtime>(app_handle: AppHandle<R>) -> Result<(), Error>;
     #[taurpc(alias = "downloadGuideFromServer")]
     async fn download_guide_from_server<R: Runtime>(
         app_handle: AppHandle<R>,
@@ -1336,6 +2553,25 @@ pub trait GuidesApi {
     async fn update_all_at_once<R: Runtime>(
         app_handle: AppHandle<R>,
     ) -> Result<HashMap<u32, UpdateAllAtOnceResult>, Error>;
+    #[taurpc(alias = "updateGuides")]
+    async fn update_guides<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_ids: Vec<u32>,
+    ) -> Result<HashMap<u32, UpdateAllAtOnceResult>, Error>;
+    #[taurpc(alias = "getUpdatePolicies")]
+    async fn get_update_policies<R: Runtime>(
+        app_handle: AppHandle<R>,
+    ) -> Result<GuideUpdatePolicies, Error>;
+    #[taurpc(alias = "setUpdatePolicy")]
+    async fn set_update_policy<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        policy: UpdatePolicy,
+    ) -> Result<GuideUpdatePolicies, Error>;
+    #[taurpc(alias = "getLastChangelog")]
+    async fn get_last_changelog<R: Runtime>(
+        app_handle: AppHandle<R>,
+    ) -> Result<Option<GuidesChangelog>, Error>;
     #[taurpc(alias = "hasGuidesNotUpdated")]
     async fn has_guides_not_updated<R: Runtime>(app_handle: AppHandle<R>) -> Result<bool, Error>;
     #[taurpc(alias = "deleteGuidesFromSystem")]
@@ -1347,6 +2583,18 @@ pub trait GuidesApi {
     async fn copy_current_guide_step<R: Runtime>(app_handle: AppHandle<R>);
     #[taurpc(event, alias = "malformedGuidesRemoved")]
     async fn malformed_guides_removed(files: Vec<RemovedGuideFile>);
+    #[taurpc(event, alias = "malformedGuidesRecovered")]
+    async fn malformed_guides_recovered(report: MalformedGuidesReport);
+    #[taurpc(event, alias = "guideStepsRemapped")]
+    async fn guide_steps_remapped(guides: Vec<RemappedGuideSteps>);
+    #[taurpc(event, alias = "guidesChanged")]
+    async fn guides_changed();
+    #[taurpc(event, alias = "guidesDownloadProgress")]
+    async fn guides_download_progress(progress: GuidesDownloadProgress);
+    #[taurpc(event, alias = "guidesUpdated")]
+    async fn guides_updated(changelog: GuidesChangelog);
+    #[taurpc(event, alias = "guideUpdatesAvailable")]
+    async fn guide_updates_available(guides: Vec<AvailableGuideUpdate>);
     #[taurpc(alias = "guideExists")]
     async fn guide_exists<R: Runtime>(
         app_handle: AppHandle<R>,
@@ -1375,11 +2623,184 @@ pub trait GuidesApi {
         profile_id: String,
         guide_ids: Vec<u32>,
     ) -> Result<(), Error>;
+    #[taurpc(alias = "getLastSession")]
+    async fn get_last_session<R: Runtime>(
+        app_handle: AppHandle<R>,
+        profile_id: String,
+    ) -> Result<Option<GuideSession>, Error>;
     #[taurpc(alias = "removeProfileFromRecentGuides")]
     async fn remove_profile_from_recent_guides<R: Runtime>(
         app_handle: AppHandle<R>,
         profile_id: String,
     ) -> Result<(), Error>;
+    async fn search<R: Runtime>(
+        app_handle: AppHandle<R>,
+        query: String,
+    ) -> Result<Vec<GuideSearchResult>, Error>;
+    #[taurpc(alias = "getGuideVersions")]
+    async fn get_guide_versions<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<Vec<GuideVersion>, Error>;
+    #[taurpc(alias = "diffGuideVersions")]
+    async fn diff_guide_versions<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from: Option<String>,
+        to: Option<String>,
+    ) -> Result<Vec<StepChange>, Error>;
+    #[taurpc(alias = "rollbackGuide")]
+    async fn rollback_guide<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        version: String,
+    ) -> Result<GuideRollback, Error>;
+    #[taurpc(alias = "getQuarantinedGuides")]
+    async fn get_quarantined_guides<R: Runtime>(
+        app_handle: AppHandle<R>,
+    ) -> Result<Vec<QuarantinedGuideFile>, Error>;
+    #[taurpc(alias = "restoreQuarantinedGuide")]
+    async fn restore_quarantined_guide<R: Runtime>(
+        app_handle: AppHandle<R>,
+        entry: String,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "purgeQuarantinedGuides")]
+    async fn purge_quarantined_guides<R: Runtime>(
+        app_handle: AppHandle<R>,
+        entries: Option<Vec<String>>,
+    ) -> Result<(), Error>;
+    #[taurpc(alias = "exportBundle")]
+    async fn export_bundle<R: Runtime>(
+        app_handle: AppHandle<R>,
+        path: String,
+        guide_ids: Vec<u32>,
+        folders: Vec<String>,
+        include_profile: bool,
+    ) -> Result<BundleExport, Error>;
+    #[taurpc(alias = "importBundle")]
+    async fn import_bundle<R: Runtime>(
+        app_handle: AppHandle<R>,
+        path: String,
+        target_folder: String,
+        conflict: BundleConflictStrategy,
+        include_profile: bool,
+    ) -> Result<BundleImport, Error>;
+    #[taurpc(alias = "createLocalGuide")]
+    async fn create_local_guide<R: Runtime>(
+        app_handle: AppHandle<R>,
+        folder: String,
+        name: String,
+        description: Option<String>,
+        lang: GuideLang,
+        game_type: GameType,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "updateLocalGuide")]
+    async fn update_local_guide<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        name: String,
+        description: Option<String>,
+        lang: GuideLang,
+        game_type: GameType,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "insertLocalGuideStep")]
+    async fn insert_local_guide_step<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        index: Option<u32>,
+        step: GuideStep,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "updateLocalGuideStep")]
+    async fn update_local_guide_step<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        index: u32,
+        step: GuideStep,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "moveLocalGuideStep")]
+    async fn move_local_guide_step<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from: u32,
+        to: u32,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "deleteLocalGuideStep")]
+    async fn delete_local_guide_step<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        index: u32,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "createFolder")]
+    async fn create_folder<R: Runtime>(
+        app_handle: AppHandle<R>,
+        folder: String,
+    ) -> Result<(), Error>;
+    #[taurpc(alias = "renameFolder")]
+    async fn rename_folder<R: Runtime>(
+        app_handle: AppHandle<R>,
+        folder: String,
+        name: String,
+    ) -> Result<(), Error>;
+    #[taurpc(alias = "moveFolder")]
+    async fn move_folder<R: Runtime>(
+        app_handle: AppHandle<R>,
+        folder: String,
+        destination: String,
+    ) -> Result<(), Error>;
+    #[taurpc(alias = "moveGuide")]
+    async fn move_guide<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        destination: String,
+    ) -> Result<GuideWithSteps, Error>;
+    #[taurpc(alias = "getGuideDependencies")]
+    async fn get_guide_dependencies<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: Option<u32>,
+    ) -> Result<GuideDependencies, Error>;
+    #[taurpc(alias = "downloadLinkedGuides")]
+    async fn download_linked_guides<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: Option<u32>,
+        folder: String,
+        recursive: bool,
+    ) -> Result<LinkedGuidesDownload, Error>;
+    #[taurpc(alias = "getGuideShoppingList")]
+    async fn get_guide_shopping_list<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from_step: Option<u32>,
+        to_step: Option<u32>,
+    ) -> Result<Vec<ShoppingListEntry>, Error>;
+    #[taurpc(alias = "getQuestIndex")]
+    async fn get_quest_index<R: Runtime>(
+        app_handle: AppHandle<R>,
+    ) -> Result<Vec<IndexedQuest>, Error>;
+    #[taurpc(alias = "getQuestCompletions")]
+    async fn get_quest_completions<R: Runtime>(
+        app_handle: AppHandle<R>,
+        quest_name: String,
+    ) -> Result<Vec<QuestCompletion>, Error>;
+    #[taurpc(alias = "getSkippableSteps")]
+    async fn get_skippable_steps<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<Vec<u32>, Error>;
+    #[taurpc(alias = "getGuideRewards")]
+    async fn get_guide_rewards<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<GuideRewards, Error>;
+    async fn lint<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        check_images: bool,
+    ) -> Result<GuideLintReport, Error>;
+    #[taurpc(alias = "makeGuideAvailableOffline")]
+    async fn make_guide_available_offline<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<OfflineGuideImages, Error>;
 }
 
 #[derive(Clone)]
@@ -1405,6 +2826,22 @@ impl GuidesApi for GuidesApiImpl {
         get_guides_or_folder_from_handle(&app, folder)
     }
 
+    async fn get_guide_headers<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        folder: Option<String>,
+    ) -> Result<Vec<GuideHeaderOrFolder>, Error> {
+        crate::guide_headers::get_guide_headers_or_folders(&app_handle, folder)
+    }
+
+    async fn get_guide<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<GuideWithSteps, Error> {
+        get_installed_guide(&app_handle, guide_id)
+    }
+
     async fn get_guide_from_server<R: Runtime>(
         self,
         app_handle: AppHandle<R>,
@@ -1421,6 +2858,28 @@ impl GuidesApi for GuidesApiImpl {
         fetch_guides_from_server(&app_handle, status).await
     }
 
+    async fn get_catalog<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        status: Option<Status>,
+    ) -> Result<GuideCatalog, Error> {
+        get_catalog(&app_handle, status).await
+    }
+
+    async fn query_guides<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        query: GuideQuery,
+    ) -> Result<GuidesPage, Error> {
+        query_guides(&app_handle, query).await
+    }
+
+    async fn mark_catalog_visited<R: Runtime>(self, app_handle: AppHandle<R>) -> Result<(), Error> {
+        mark_catalog_visited(&app_handle);
+
+        Ok(())
+    }
+
     async fn download_guide_from_server<R: Runtime>(
         self,
         app: AppHandle<R>,
@@ -1449,6 +2908,42 @@ impl GuidesApi for GuidesApiImpl {
         update_all_guides_batch(&app_handle).await
     }
 
+    async fn update_guides<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_ids: Vec<u32>,
+    ) -> Result<HashMap<u32, UpdateAllAtOnceResult>, Error> {
+        let guide_ids = guide_ids
+            .into_iter()
+            .filter(|id| !is_local_guide(*id))
+            .collect();
+
+        update_guides_by_ids(&app_handle, guide_ids).await
+    }
+
+    async fn get_update_policies<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+    ) -> Result<GuideUpdatePolicies, Error> {
+        get_update_policies(&app_handle)
+    }
+
+    async fn set_update_policy<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        policy: UpdatePolicy,
+    ) -> Result<GuideUpdatePolicies, Error> {
+        set_update_policy(&app_handle, guide_id, policy)
+    }
+
+    async fn get_last_changelog<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+    ) -> Result<Option<GuidesChangelog>, Error> {
+        get_last_changelog(&app_handle)
+    }
+
     async fn has_guides_not_updated<R: Runtime>(
         self,
         app_handle: AppHandle<R>,
@@ -1507,6 +3002,14 @@ impl GuidesApi for GuidesApiImpl {
         set_recent_guides(app_handle, profile_id, guide_ids)
     }
 
+    async fn get_last_session<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        profile_id: String,
+    ) -> Result<Option<GuideSession>, Error> {
+        get_last_session(app_handle, profile_id)
+    }
+
     async fn remove_profile_from_recent_guides<R: Runtime>(
         self,
         app_handle: AppHandle<R>,
@@ -1514,4 +3017,327 @@ impl GuidesApi for GuidesApiImpl {
     ) -> Result<(), Error> {
         remove_profile_from_recent_guides(app_handle, profile_id)
     }
+
+    async fn search<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        query: String,
+    ) -> Result<Vec<GuideSearchResult>, Error> {
+        search_guides(&app_handle, query)
+    }
+
+    async fn get_guide_versions<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<Vec<GuideVersion>, Error> {
+        crate::guide_versions::get_guide_versions(&app_handle, guide_id)
+    }
+
+    async fn diff_guide_versions<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from: Option<String>,
+        to: Option<String>,
+    ) -> Result<Vec<StepChange>, Error> {
+        crate::guide_versions::diff_guide_versions(&app_handle, guide_id, from, to)
+    }
+
+    async fn rollback_guide<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        version: String,
+    ) -> Result<GuideRollback, Error> {
+        let guide = crate::guide_versions::rollback_guide(&app_handle, guide_id, version)?;
+
+        Ok(GuideRollback {
+            guide,
+            update_policy: freeze_rolled_back_guide(&app_handle, guide_id)?,
+        })
+    }
+
+    async fn get_quarantined_guides<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+    ) -> Result<Vec<QuarantinedGuideFile>, Error> {
+        crate::guide_quarantine::list_quarantined_guides(
+            &app_handle.path().app_guides_quarantine_dir(),
+        )
+    }
+
+    async fn restore_quarantined_guide<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        entry: String,
+    ) -> Result<GuideWithSteps, Error> {
+        crate::guide_quarantine::restore_quarantined_guide(&app_handle, entry).await
+    }
+
+    async fn purge_quarantined_guides<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        entries: Option<Vec<String>>,
+    ) -> Result<(), Error> {
+        crate::guide_quarantine::purge_quarantined_guides(&app_handle, entries)
+    }
+
+    async fn export_bundle<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        path: String,
+        guide_ids: Vec<u32>,
+        folders: Vec<String>,
+        include_profile: bool,
+    ) -> Result<BundleExport, Error> {
+        crate::guide_bundle::export_bundle(&app_handle, path, guide_ids, folders, include_profile)
+    }
+
+    async fn import_bundle<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        path: String,
+        target_folder: String,
+        conflict: BundleConflictStrategy,
+        include_profile: bool,
+    ) -> Result<BundleImport, Error> {
+        crate::guide_bundle::import_bundle(
+            &app_handle,
+            path,
+            target_folder,
+            conflict,
+            include_profile,
+        )
+    }
+
+    async fn create_local_guide<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        folder: String,
+        name: String,
+        description: Option<String>,
+        lang: GuideLang,
+        game_type: GameType,
+    ) -> Result<GuideWithSteps, Error> {
+        create_local_guide(&app_handle, folder, name, description, lang, game_type)
+    }
+
+    async fn update_local_guide<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        name: String,
+        description: Option<String>,
+        lang: GuideLang,
+        game_type: GameType,
+    ) -> Result<GuideWithSteps, Error> {
+        edit_local_guide(&app_handle, guide_id, |guide| {
+            guide.name = name;
+            guide.description = description;
+            guide.lang = lang;
+            guide.game_type = game_type;
+
+            Ok(())
+        })
+    }
+
+    async fn insert_local_guide_step<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        index: Option<u32>,
+        step: GuideStep,
+    ) -> Result<GuideWithSteps, Error> {
+        edit_local_guide(&app_handle, guide_id, |guide| {
+            let index = index.map_or(guide.steps.len(), |index| index as usize);
+
+            if index > guide.steps.len() {
+                return Err(Error::StepOutOfRange(index as u32));
+            }
+
+            guide.steps.insert(index, step);
+
+            Ok(())
+        })
+    }
+
+    async fn update_local_guide_step<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        index: u32,
+        step: GuideStep,
+    ) -> Result<GuideWithSteps, Error> {
+        edit_local_guide(&app_handle, guide_id, |guide| {
+            let index = check_step_index(guide, index)?;
+
+            guide.steps[index] = step;
+
+            Ok(())
+        })
+    }
+
+    async fn move_local_guide_step<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from: u32,
+        to: u32,
+    ) -> Result<GuideWithSteps, Error> {
+        edit_local_guide(&app_handle, guide_id, |guide| {
+            let from = check_step_index(guide, from)?;
+            let to = check_step_index(guide, to)?;
+            let step = guide.steps.remove(from);
+
+            guide.steps.insert(to, step);
+
+            Ok(())
+        })
+    }
+
+    async fn delete_local_guide_step<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        index: u32,
+    ) -> Result<GuideWithSteps, Error> {
+        edit_local_guide(&app_handle, guide_id, |guide| {
+            let index = check_step_index(guide, index)?;
+
+            guide.steps.remove(index);
+
+            Ok(())
+        })
+    }
+
+    async fn create_folder<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        folder: String,
+    ) -> Result<(), Error> {
+        create_folder(&app_handle, folder)
+    }
+
+    async fn rename_folder<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        folder: String,
+        name: String,
+    ) -> Result<(), Error> {
+        rename_folder(&app_handle, folder, name)
+    }
+
+    async fn move_folder<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        folder: String,
+        destination: String,
+    ) -> Result<(), Error> {
+        move_folder(&app_handle, folder, destination)
+    }
+
+    async fn move_guide<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        destination: String,
+    ) -> Result<GuideWithSteps, Error> {
+        move_guide(&app_handle, guide_id, destination)
+    }
+
+    async fn get_guide_dependencies<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: Option<u32>,
+    ) -> Result<GuideDependencies, Error> {
+        crate::guide_links::get_guide_dependencies(&app_handle, guide_id)
+    }
+
+    async fn download_linked_guides<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: Option<u32>,
+        folder: String,
+        recursive: bool,
+    ) -> Result<LinkedGuidesDownload, Error> {
+        crate::guide_links::download_linked_guides(&app_handle, guide_id, folder, recursive).await
+    }
+
+    async fn get_guide_shopping_list<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from_step: Option<u32>,
+        to_step: Option<u32>,
+    ) -> Result<Vec<ShoppingListEntry>, Error> {
+        crate::shopping_list::get_guide_shopping_list(&app_handle, guide_id, from_step, to_step)
+    }
+
+    async fn get_quest_index<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+    ) -> Result<Vec<IndexedQuest>, Error> {
+        let progresses = load_library_and_progresses(&app_handle)?;
+
+        Ok(app_handle
+            .state::<GuidesQuestIndex>()
+            .quests(&app_handle.state::<GuidesLibrary>(), &progresses))
+    }
+
+    async fn get_quest_completions<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        quest_name: String,
+    ) -> Result<Vec<QuestCompletion>, Error> {
+        let progresses = load_library_and_progresses(&app_handle)?;
+
+        Ok(app_handle.state::<GuidesQuestIndex>().completions(
+            &app_handle.state::<GuidesLibrary>(),
+            &quest_name,
+            &progresses,
+        ))
+    }
+
+    async fn get_skippable_steps<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<Vec<u32>, Error> {
+        let guide = get_installed_guide(&app_handle, guide_id)?;
+        let conf =
+            crate::conf::get_conf(&app_handle).map_err(|err| Error::Profile(err.to_string()))?;
+        let Some(profile) = conf.profiles.iter().find(|p| p.id == conf.profile_in_use) else {
+            return Ok(vec![]);
+        };
+
+        Ok(extract_skippable_steps(&guide, |name| {
+            crate::conf::is_quest_completed(profile, name)
+        }))
+    }
+
+    async fn get_guide_rewards<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<GuideRewards, Error> {
+        crate::guide_rewards::get_guide_rewards(&app_handle, guide_id).await
+    }
+
+    async fn lint<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        check_images: bool,
+    ) -> Result<GuideLintReport, Error> {
+        crate::guide_lint::lint_guide(&app_handle, guide_id, check_images).await
+    }
+
+    async fn make_guide_available_offline<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<OfflineGuideImages, Error> {
+        crate::image_cache::cache_guide_images(&app_handle, guide_id).await
+    }
 }
@@ -1,6 +1,5 @@
 use serde::Serialize;
-use tauri::{AppHandle, Manager, Runtime};
-use tauri_plugin_http::reqwest;
+use tauri::{AppHandle, Runtime};
 
 #[derive(Debug, Serialize, thiserror::Error, taurpc::specta::Type)]
 #[specta(rename = "ImageError")]
@@ -9,6 +8,10 @@ pub enum Error {
     RequestImage(String),
     #[error("ConvertToBytes({0})")]
     ConvertToBytes(String),
+    #[error("CacheImage({0})")]
+    CacheImage(String),
+    #[error("ImageLargerThanCache({0})")]
+    ImageLargerThanCache(u32),
 }
 
 #[taurpc::procedures(path = "image", export_to = "../src/ipc/bindings.ts")]
@@ -30,16 +33,8 @@ impl ImageApi for ImageApiImpl {
         app_handle: AppHandle<R>,
         url: String,
     ) -> Result<Vec<u8>, Error> {
-        let http_client = app_handle.state::<reqwest::Client>();
-
-        http_client
-            .get(url)
-            .send()
-            .await
-            .map_err(|err| Error::RequestImage(err.to_string()))?
-            .bytes()
+        crate::image_cache::get_image(&app_handle, &url)
             .await
-            .map(|bytes| bytes.to_vec())
-            .map_err(|err| Error::ConvertToBytes(err.to_string()))
+            .map(|image| image.bytes)
     }
 }
@@ -0,0 +1,398 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::Mutex,
+};
+
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{http, AppHandle, Manager, Runtime, UriSchemeContext, UriSchemeResponder};
+use tauri_plugin_http::reqwest;
+
+use crate::{image::Error, storage::ReadError, tauri_api_ext::ImageCachePathExt};
+
+// Constants
+
+/// Scheme serving the cached images to the webview, the remote url is percent-encoded as the
+/// path: `guide-image://localhost/<url>` (`http://guide-image.localhost/<url>` on Windows).
+pub const IMAGE_URI_SCHEME: &str = "guide-image";
+const INDEX_FILE: &str = "index.json";
+
+// Structs
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedImage {
+    url: String,
+    size: u64,
+    content_type: Option<String>,
+    /// Unix timestamp in milliseconds.
+    last_access: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    images: HashMap<String, CachedImage>,
+}
+
+pub struct Image {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Default)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineGuideImages {
+    /// Images of the guide now in the cache, including the ones which already were.
+    pub cached: u32,
+    /// Urls which could not be downloaded with the reason.
+    pub failed: HashMap<String, String>,
+}
+
+/// Disk cache of the remote images displayed in the guides, evicting the least recently used
+/// images above the size set in the conf. The index is loaded on first use and written along the
+/// images, the access times of the images read since are saved with it.
+#[derive(Default)]
+pub struct ImageCache {
+    index: Mutex<Option<CacheIndex>>,
+}
+
+// Implementations
+
+impl ImageCache {
+    fn with_index<T>(&self, dir: &Path, f: impl FnOnce(&mut CacheIndex) -> T) -> T {
+        let mut lock = self.index.lock().unwrap();
+        let index = lock.get_or_insert_with(|| read_index(dir));
+
+        f(index)
+    }
+
+    fn contains(&self, dir: &Path, url: &str) -> bool {
+        let key = cache_key(url);
+
+        self.with_index(dir, |index| index.images.contains_key(&key))
+    }
+
+    fn get(&self, dir: &Path, url: &str) -> Option<Image> {
+        let key = cache_key(url);
+        let content_type = self.with_index(dir, |index| {
+            let image = index.images.get_mut(&key)?;
+
+            image.last_access = chrono::Utc::now().timestamp_millis();
+
+            Some(image.content_type.clone())
+        })?;
+
+        match fs::read(dir.join(&key)) {
+            Ok(bytes) => Some(Image {
+                bytes,
+                content_type,
+            }),
+            Err(err) => {
+                warn!("[ImageCache] cannot read cached image {}: {}", url, err);
+
+                self.with_index(dir, |index| index.images.remove(&key));
+
+                None
+            }
+        }
+    }
+
+    fn insert(&self, dir: &Path, url: &str, image: &Image, max_size: u64) -> Result<(), Error> {
+        let key = cache_key(url);
+        let size = image.bytes.len() as u64;
+
+        // it would be evicted right away, along with every other image
+        if size > max_size {
+            return Err(Error::ImageLargerThanCache(
+                u32::try_from(size).unwrap_or(u32::MAX),
+            ));
+        }
+
+        fs::create_dir_all(dir).map_err(|err| Error::CacheImage(err.to_string()))?;
+        crate::storage::write(&dir.join(&key), &image.bytes)
+            .map_err(|err| Error::CacheImage(err.to_string()))?;
+
+        self.with_index(dir, |index| {
+            index.images.insert(
+                key,
+                CachedImage {
+                    url: url.to_string(),
+                    size,
+                    content_type: image.content_type.clone(),
+                    last_access: chrono::Utc::now().timestamp_millis(),
+                },
+            );
+
+            for key in images_to_evict(&index.images, max_size) {
+                debug!("[ImageCache] evicting {}", index.images[&key].url);
+
+                index.images.remove(&key);
+
+                if let Err(err) = fs::remove_file(dir.join(&key)) {
+                    warn!("[ImageCache] cannot remove evicted image {}: {}", key, err);
+                }
+            }
+
+            write_index(dir, index)
+        })
+    }
+}
+
+// Public Functions
+
+/// Image at `url`, from the cache when available, otherwise downloaded then cached.
+pub async fn get_image<R: Runtime>(app_handle: &AppHandle<R>, url: &str) -> Result<Image, Error> {
+    let dir = app_handle.path().app_image_cache_dir();
+    let cache = app_handle.state::<ImageCache>();
+
+    if let Some(image) = cache.get(&dir, url) {
+        return Ok(image);
+    }
+
+    let image = download_image(app_handle, url).await?;
+
+    // the image is still displayed when it cannot be cached
+    if let Err(err) = cache.insert(&dir, url, &image, max_cache_size(app_handle)) {
+        warn!("[ImageCache] cannot cache {}: {}", url, err);
+    }
+
+    Ok(image)
+}
+
+/// Download every image of a guide missing from the cache, so it can be displayed offline.
+pub async fn cache_guide_images<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<OfflineGuideImages, crate::guides::Error> {
+    let guide = crate::guides::get_installed_guide(app_handle, guide_id)?;
+    let dir = app_handle.path().app_image_cache_dir();
+    let cache = app_handle.state::<ImageCache>();
+    let max_size = max_cache_size(app_handle);
+    let mut visited = HashSet::new();
+    let mut result = OfflineGuideImages::default();
+
+    info!("[ImageCache] caching images of guide {}", guide_id);
+
+    for url in guide
+        .steps
+        .iter()
+        .flat_map(|step| image_urls(&step.web_text))
+    {
+        if !is_http_url(&url) || !visited.insert(url.clone()) {
+            continue;
+        }
+
+        if cache.contains(&dir, &url) {
+            result.cached += 1;
+            continue;
+        }
+
+        let cached = match download_image(app_handle, &url).await {
+            Ok(image) => cache.insert(&dir, &url, &image, max_size),
+            Err(err) => Err(err),
+        };
+
+        match cached {
+            Ok(()) => result.cached += 1,
+            Err(err) => {
+                warn!("[ImageCache] cannot cache image {}: {}", url, err);
+
+                result.failed.insert(url, err.to_string());
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Urls of the `<img>` of a step.
+pub fn image_urls(web_text: &str) -> Vec<String> {
+    if !web_text.contains("<img") {
+        return vec![];
+    }
+
+    let selector = scraper::Selector::parse("img").unwrap();
+
+    scraper::Html::parse_fragment(web_text)
+        .select(&selector)
+        .map(|element| {
+            element
+                .value()
+                .attr("src")
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Handler of the `guide-image` scheme.
+pub fn handle_image_request<R: Runtime>(
+    ctx: UriSchemeContext<'_, R>,
+    request: http::Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app_handle = ctx.app_handle().clone();
+    let path = request.uri().path().trim_start_matches('/').to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let url = urlencoding::decode(&path)
+            .map(|url| url.into_owned())
+            .unwrap_or_default();
+
+        if !is_http_url(&url) {
+            return responder.respond(response(http::StatusCode::BAD_REQUEST, None, vec![]));
+        }
+
+        match get_image(&app_handle, &url).await {
+            Ok(image) => responder.respond(response(
+                http::StatusCode::OK,
+                image.content_type,
+                image.bytes,
+            )),
+            Err(err) => {
+                warn!("[ImageCache] cannot serve image {}: {}", url, err);
+
+                responder.respond(response(http::StatusCode::BAD_GATEWAY, None, vec![]))
+            }
+        }
+    });
+}
+
+// Private Functions
+
+fn cache_key(url: &str) -> String {
+    format!("{:x}", Sha256::digest(url.as_bytes()))
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+fn max_cache_size<R: Runtime>(app_handle: &AppHandle<R>) -> u64 {
+    let size_mb = crate::conf::get_conf(app_handle)
+        .map(|conf| conf.image_cache_max_size_mb)
+        .unwrap_or(crate::conf::DEFAULT_IMAGE_CACHE_MAX_SIZE_MB);
+
+    size_mb as u64 * 1024 * 1024
+}
+
+async fn download_image<R: Runtime>(app_handle: &AppHandle<R>, url: &str) -> Result<Image, Error> {
+    let http_client = app_handle.state::<reqwest::Client>();
+
+    let response = http_client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|err| Error::RequestImage(err.to_string()))?;
+
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|err| Error::ConvertToBytes(err.to_string()))?;
+
+    Ok(Image {
+        bytes: bytes.to_vec(),
+        content_type,
+    })
+}
+
+/// Keys of the least recently used images to remove so the cache fits in `max_size`.
+fn images_to_evict(images: &HashMap<String, CachedImage>, max_size: u64) -> Vec<String> {
+    let mut total_size = images.values().map(|image| image.size).sum::<u64>();
+    let mut by_access = images.iter().collect::<Vec<_>>();
+
+    by_access.sort_by_key(|(_, image)| image.last_access);
+
+    let mut evicted = vec![];
+
+    for (key, image) in by_access {
+        if total_size <= max_size {
+            break;
+        }
+
+        total_size -= image.size;
+        evicted.push(key.clone());
+    }
+
+    evicted
+}
+
+fn read_index(dir: &Path) -> CacheIndex {
+    match crate::storage::read_json::<CacheIndex>(&dir.join(INDEX_FILE)) {
+        Ok(index) => index.unwrap_or_default(),
+        Err(ReadError::Io(err)) => {
+            warn!("[ImageCache] cannot read index, starting empty: {}", err);
+            CacheIndex::default()
+        }
+        Err(ReadError::Malformed(err)) => {
+            warn!("[ImageCache] malformed index, starting empty: {}", err);
+            CacheIndex::default()
+        }
+    }
+}
+
+fn write_index(dir: &Path, index: &CacheIndex) -> Result<(), Error> {
+    let json =
+        crate::json::serialize_pretty(index).map_err(|err| Error::CacheImage(err.to_string()))?;
+
+    crate::storage::write(&dir.join(INDEX_FILE), json)
+        .map_err(|err| Error::CacheImage(err.to_string()))
+}
+
+fn response(
+    status: http::StatusCode,
+    content_type: Option<String>,
+    body: Vec<u8>,
+) -> http::Response<Vec<u8>> {
+    let mut builder = http::Response::builder().status(status);
+
+    if let Some(content_type) = content_type {
+        builder = builder.header(http::header::CONTENT_TYPE, content_type);
+    }
+
+    builder.body(body).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{images_to_evict, CachedImage};
+
+    fn image(size: u64, last_access: i64) -> CachedImage {
+        CachedImage {
+            url: String::new(),
+            size,
+            content_type: None,
+            last_access,
+        }
+    }
+
+    #[test]
+    fn images_to_evict_removes_least_recently_used_first() {
+        let images = HashMap::from([
+            ("a".to_string(), image(40, 3)),
+            ("b".to_string(), image(30, 1)),
+            ("c".to_string(), image(50, 2)),
+        ]);
+
+        assert!(images_to_evict(&images, 120).is_empty());
+        assert_eq!(images_to_evict(&images, 100), vec!["b".to_string()]);
+        assert_eq!(
+            images_to_evict(&images, 60),
+            vec!["b".to_string(), "c".to_string()]
+        );
+    }
+}
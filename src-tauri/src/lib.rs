@@ -5,19 +5,28 @@ use crate::conf::{ConfApi, ConfApiImpl};
 use crate::deep_link::{DeepLinkApi, DeepLinkApiImpl};
 use crate::dofusdb::{DofusDbApi, DofusDbApiImpl};
 use crate::first_start::handle_first_start_setup;
-use crate::guides::{GuidesApi, GuidesApiImpl};
+use crate::guide_catalog::GuideCatalogCache;
+use crate::guide_headers::GuideHeadersIndex;
+use crate::guide_library::{watch_guides_dir, GuidesLibrary};
+use crate::guide_rewards::QuestRewardsCache;
+use crate::guides::{AppStartedAt, GuidesApi, GuidesApiImpl};
 use crate::image::{ImageApi, ImageApiImpl};
+use crate::image_cache::{handle_image_request, ImageCache, IMAGE_URI_SCHEME};
 use crate::image_viewer::{ImageViewerApi, ImageViewerApiImpl};
 use crate::notifications::{NotificationApi, NotificationApiImpl};
 use crate::oauth::{OAuthApi, OAuthApiImpl};
 use crate::pinned_guides::{PinnedGuidesApi, PinnedGuidesApiImpl};
+use crate::quest_index::GuidesQuestIndex;
+use crate::search::GuidesSearchIndex;
 use crate::security::{SecurityApi, SecurityApiImpl};
 use crate::shortcut::{handle_shortcuts, ShortcutsApi, ShortcutsApiImpl};
 use crate::step_notes::{StepNotesApi, StepNotesApiImpl};
 use crate::sync::{SyncApi, SyncApiImpl};
+use crate::travel::{TravelApi, TravelApiImpl};
 use crate::update::{UpdateApi, UpdateApiImpl};
 use crate::user::{UserApi, UserApiImpl};
 use crate::window_manager::WindowManager;
+use crate::zaap::ZaapTable;
 use log::{error, info, LevelFilter};
 use report::{ReportApi, ReportApiImpl};
 use tauri::Manager;
@@ -35,8 +44,21 @@ mod deep_link;
 mod dofusdb;
 mod event;
 mod first_start;
+mod guide_bundle;
+mod guide_catalog;
+mod guide_changelog;
+mod guide_diff;
+mod guide_headers;
+mod guide_library;
+mod guide_links;
+mod guide_lint;
+mod guide_quarantine;
+mod guide_rewards;
+mod guide_update_policy;
+mod guide_versions;
 mod guides;
 mod image;
+mod image_cache;
 mod image_viewer;
 mod item;
 mod json;
@@ -44,15 +66,23 @@ mod notifications;
 mod oauth;
 mod pinned_guides;
 mod quest;
+mod quest_index;
 mod report;
+mod search;
 mod security;
+mod shopping_list;
 mod shortcut;
 mod step_notes;
+mod storage;
 mod sync;
 mod tauri_api_ext;
+#[cfg(test)]
+mod test_guides;
+mod travel;
 mod update;
 mod user;
 mod window_manager;
+mod zaap;
 
 #[cfg(dev)]
 const LOG_TARGETS: [Target; 2] = [
@@ -126,6 +156,7 @@ pub fn run() {
     }
 
     let app = builder
+        .register_asynchronous_uri_scheme_protocol(IMAGE_URI_SCHEME, handle_image_request)
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_log::Builder::new().build())
@@ -185,7 +216,8 @@ pub fn run() {
         .merge(ShortcutsApiImpl.into_handler())
         .merge(SyncApiImpl.into_handler())
         .merge(StepNotesApiImpl.into_handler())
-        .merge(PinnedGuidesApiImpl.into_handler());
+        .merge(PinnedGuidesApiImpl.into_handler())
+        .merge(TravelApiImpl.into_handler());
 
     #[cfg(not(debug_assertions))]
     add_breadcrumb(Breadcrumb {
@@ -202,7 +234,16 @@ pub fn run() {
             .unwrap();
 
         app.manage(http_client.clone());
+        app.manage(AppStartedAt::default());
         app.manage(WindowManager::new());
+        app.manage(GuidesLibrary::default());
+        app.manage(GuideCatalogCache::default());
+        app.manage(GuideHeadersIndex::default());
+        app.manage(GuidesSearchIndex::default());
+        app.manage(GuidesQuestIndex::default());
+        app.manage(QuestRewardsCache::default());
+        app.manage(ImageCache::default());
+        app.manage(ZaapTable::default());
 
         #[cfg(not(debug_assertions))]
         add_breadcrumb(Breadcrumb {
@@ -223,6 +264,8 @@ pub fn run() {
             capture_error(&err);
         }
 
+        watch_guides_dir(app.handle().clone());
+
         if let Err(err) = step_notes::ensure_step_notes_file(app.handle()) {
             error!("[Lib] failed to ensure step notes: {:?}", err);
             #[cfg(not(debug_assertions))]
@@ -1,8 +1,7 @@
-use crate::{api::GANYMEDE_API, tauri_api_ext::ViewedNotificationsPathExt};
+use crate::{api::GANYMEDE_API, storage::ReadError, tauri_api_ext::ViewedNotificationsPathExt};
 use log::{debug, info};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::fs;
 use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_http::reqwest;
 
@@ -55,15 +54,11 @@ impl ViewedNotifications {
     pub fn get<R: Runtime>(app_handle: &AppHandle<R>) -> Result<ViewedNotifications, Error> {
         let viewed_notifications_path = app_handle.path().app_viewed_notifications_file();
 
-        let file = fs::read_to_string(viewed_notifications_path);
-
-        match file {
-            Err(err) => match err.kind() {
-                std::io::ErrorKind::NotFound => Ok(ViewedNotifications::default()),
-                _ => Err(Error::UnhandledIo(err.to_string())),
-            },
-            Ok(file) => Ok(crate::json::from_str::<ViewedNotifications>(file.as_str())
-                .map_err(Error::Malformed)?),
+        match crate::storage::read_json::<ViewedNotifications>(&viewed_notifications_path) {
+            Ok(Some(viewed)) => Ok(viewed),
+            Ok(None) => Ok(ViewedNotifications::default()),
+            Err(ReadError::Io(err)) => Err(Error::UnhandledIo(err)),
+            Err(ReadError::Malformed(err)) => Err(Error::Malformed(err)),
         }
     }
 
@@ -74,7 +69,7 @@ impl ViewedNotifications {
         let json =
             crate::json::serialize_pretty(self).map_err(Error::SerializeViewedNotifications)?;
 
-        fs::write(viewed_notifications_path, json)
+        crate::storage::write(&viewed_notifications_path, json)
             .map_err(|err| Error::SaveViewedNotifications(err.to_string()))
     }
 
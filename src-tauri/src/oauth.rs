@@ -1,5 +1,6 @@
 use crate::api::GANYMEDE_WEBSITE;
 use crate::json;
+use crate::storage::ReadError;
 use crate::tauri_api_ext::AuthPathExt;
 use base64::{engine::general_purpose, Engine as _};
 use log::{debug, error, info};
@@ -7,7 +8,6 @@ use rand::Rng;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::fs;
 use std::sync::{LazyLock, Mutex};
 use tauri::{AppHandle, Manager, Runtime};
 use tauri_plugin_http::reqwest;
@@ -291,7 +291,7 @@ fn save_auth_tokens<R: Runtime>(
 ) -> Result<(), Error> {
     let path = app_handle.path().app_auth_file();
     let json = json::serialize_pretty(tokens)?;
-    fs::write(&path, json).map_err(|e| Error::SaveAuth(e.to_string()))?;
+    crate::storage::write(&path, json).map_err(|e| Error::SaveAuth(e.to_string()))?;
     Ok(())
 }
 
@@ -299,7 +299,7 @@ fn clean_auth_tokens<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Option<Aut
     let path = app_handle.path().app_auth_file();
 
     if path.exists() {
-        fs::remove_file(&path).map_err(|e| Error::CleanAuth(e.to_string()))?;
+        crate::storage::remove(&path).map_err(|e| Error::CleanAuth(e.to_string()))?;
         info!("[OAuth] Cleared authentication tokens");
         Ok(None)
     } else {
@@ -330,13 +330,11 @@ pub fn load_auth_tokens<R: Runtime>(
     app_handle: &AppHandle<R>,
 ) -> Result<Option<AuthTokens>, Error> {
     let path = app_handle.path().app_auth_file();
-    if !path.exists() {
-        return Ok(None);
-    }
 
-    let content = fs::read_to_string(&path).map_err(|e| Error::LoadAuth(e.to_string()))?;
-    let tokens: AuthTokens = json::from_str(&content)?;
-    Ok(Some(tokens))
+    crate::storage::read_json::<AuthTokens>(&path).map_err(|e| match e {
+        ReadError::Io(err) => Error::LoadAuth(err),
+        ReadError::Malformed(err) => Error::Json(err),
+    })
 }
 
 pub async fn refresh_auth_tokens<R: Runtime>(
@@ -4,7 +4,7 @@ use log::{debug, info};
 use serde::Serialize;
 use tauri::{AppHandle, Manager, Runtime};
 
-use crate::tauri_api_ext::PinnedGuidesPathExt;
+use crate::{storage::ReadError, tauri_api_ext::PinnedGuidesPathExt};
 
 // Constants
 
@@ -66,16 +66,11 @@ impl Default for PinnedGuides {
 pub fn get_pinned_guides<R: Runtime>(app_handle: &AppHandle<R>) -> Result<PinnedGuides, Error> {
     let path = app_handle.path().app_pinned_guides_file();
 
-    let file = fs::read_to_string(path);
-
-    match file {
-        Err(err) => match err.kind() {
-            std::io::ErrorKind::NotFound => Ok(PinnedGuides::default()),
-            _ => Err(Error::UnhandledIo(err.to_string())),
-        },
-        Ok(file) => {
-            Ok(crate::json::from_str::<PinnedGuides>(file.as_str()).map_err(Error::Malformed)?)
-        }
+    match crate::storage::read_json::<PinnedGuides>(&path) {
+        Ok(Some(pinned)) => Ok(pinned),
+        Ok(None) => Ok(PinnedGuides::default()),
+        Err(ReadError::Io(err)) => Err(Error::UnhandledIo(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::Malformed(err)),
     }
 }
 
@@ -87,7 +82,7 @@ pub fn save_pinned_guides<R: Runtime>(
 
     let json = crate::json::serialize_pretty(pinned).map_err(Error::SerializePinnedGuides)?;
 
-    fs::write(path, json).map_err(|err| Error::SavePinnedGuides(err.to_string()))
+    crate::storage::write(&path, json).map_err(|err| Error::SavePinnedGuides(err.to_string()))
 }
 
 pub fn ensure_pinned_guides_file(app_handle: &AppHandle) -> Result<(), Error> {
@@ -20,6 +20,16 @@ pub struct Quest {
     pub steps: Vec<QuestStep>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct QuestSearch {
+    data: Vec<QuestSearchResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct QuestSearchResult {
+    id: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct QuestDescription {
     pub en: String,
@@ -45,7 +55,7 @@ pub struct QuestReward {
     #[serde(rename = "kamasRatio")]
     pub kamas_ratio: f32,
     #[serde(rename = "experienceRatio")]
-    experience_ratio: f32,
+    pub experience_ratio: f32,
     #[serde(rename = "kamasScaleWithPlayerLevel")]
     pub kamas_scale_with_player_level: bool,
 }
@@ -122,3 +132,30 @@ pub async fn get_quest(id: &u32, http_client: &reqwest::Client) -> Result<Quest,
 
     crate::json::from_str::<Quest>(text.as_str()).map_err(Error::DofusDbQuestMalformed)
 }
+
+/// Id of the quest named `name` in `lang`, `None` when DofusDB does not know it.
+pub async fn find_quest_id(
+    name: &str,
+    lang: &str,
+    http_client: &reqwest::Client,
+) -> Result<Option<u32>, Error> {
+    let res = http_client
+        .get(format!(
+            "{}/quests?name.{}={}&$limit=1&$select[]=id",
+            DOFUSDB_API,
+            lang,
+            urlencoding::encode(name)
+        ))
+        .send()
+        .await
+        .map_err(|err| Error::RequestQuest(err.to_string()))?;
+
+    let text = res
+        .text()
+        .await
+        .map_err(|err| Error::RequestQuestContent(err.to_string()))?;
+
+    crate::json::from_str::<QuestSearch>(text.as_str())
+        .map(|search| search.data.first().map(|quest| quest.id))
+        .map_err(Error::DofusDbQuestMalformed)
+}
@@ -0,0 +1,200 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    conf::Progress,
+    guide_library::{GuidesLibrary, LibraryIndex},
+    guides::{extract_guide_quests, QuestSummary, SummaryQuestStatus},
+};
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct QuestOccurrence {
+    pub guide_id: u32,
+    /// Step numbers start at 1, like in the guide summary.
+    pub statuses: Vec<SummaryQuestStatus>,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedQuest {
+    pub name: String,
+    pub occurrences: Vec<QuestOccurrence>,
+    /// Whether the active profile went through a step completing the quest in one of its guides.
+    pub completed: bool,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct QuestCompletion {
+    pub guide_id: u32,
+    pub step_number: u32,
+    pub completed: bool,
+}
+
+/// In-memory index of the quests of every downloaded guide, built from the guides library on the
+/// first query after it changed, like the search index.
+#[derive(Default)]
+pub struct GuidesQuestIndex {
+    index: LibraryIndex<Vec<QuestSummary>>,
+}
+
+// Implementations
+
+impl GuidesQuestIndex {
+    /// Every quest of the guides of `library` sorted by name, along with whether `progresses`
+    /// completed it. Nothing is found until the library is loaded.
+    pub fn quests(&self, library: &GuidesLibrary, progresses: &[Progress]) -> Vec<IndexedQuest> {
+        self.index
+            .with_index(library, extract_guide_quests, |guides| {
+                indexed_quests(guides, progresses)
+            })
+            .unwrap_or_default()
+    }
+
+    /// Guides completing the quest `name` (case insensitive) and the step where they do.
+    pub fn completions(
+        &self,
+        library: &GuidesLibrary,
+        name: &str,
+        progresses: &[Progress],
+    ) -> Vec<QuestCompletion> {
+        let name = name.trim().to_lowercase();
+
+        self.quests(library, progresses)
+            .into_iter()
+            .filter(|quest| quest.name.to_lowercase() == name)
+            .flat_map(|quest| completions(&quest.occurrences, progresses).collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+// Public Functions
+
+/// Whether a profile reached the step numbered `step_number` (starting at 1) of a guide.
+pub fn has_reached_step(progresses: &[Progress], guide_id: u32, step_number: u32) -> bool {
+    progresses
+        .iter()
+        .any(|progress| progress.id == guide_id && progress.current_step + 1 >= step_number)
+}
+
+// Private Functions
+
+fn indexed_quests(
+    guides: &HashMap<u32, Vec<QuestSummary>>,
+    progresses: &[Progress],
+) -> Vec<IndexedQuest> {
+    let mut quests = BTreeMap::<&str, Vec<QuestOccurrence>>::new();
+    let mut guide_ids = guides.keys().copied().collect::<Vec<_>>();
+
+    guide_ids.sort_unstable();
+
+    for guide_id in guide_ids {
+        for quest in &guides[&guide_id] {
+            quests
+                .entry(quest.name.as_str())
+                .or_default()
+                .push(QuestOccurrence {
+                    guide_id,
+                    statuses: quest.statuses.clone(),
+                });
+        }
+    }
+
+    quests
+        .into_iter()
+        .map(|(name, occurrences)| {
+            let completed = completions(&occurrences, progresses).any(|c| c.completed);
+
+            IndexedQuest {
+                name: name.to_string(),
+                occurrences,
+                completed,
+            }
+        })
+        .collect()
+}
+
+fn completions<'a>(
+    occurrences: &'a [QuestOccurrence],
+    progresses: &'a [Progress],
+) -> impl Iterator<Item = QuestCompletion> + 'a {
+    occurrences.iter().flat_map(move |occurrence| {
+        occurrence.statuses.iter().filter_map(move |status| {
+            let SummaryQuestStatus::Completed(step_number) = status else {
+                return None;
+            };
+
+            Some(QuestCompletion {
+                guide_id: occurrence.guide_id,
+                step_number: *step_number,
+                completed: has_reached_step(progresses, occurrence.guide_id, *step_number),
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::GuidesQuestIndex;
+    use crate::{
+        conf::Progress,
+        guide_library::GuidesLibrary,
+        test_guides::{guide, step},
+    };
+
+    #[test]
+    fn completions_report_guides_and_profile_progress() {
+        let library = GuidesLibrary::default();
+        let index = GuidesQuestIndex::default();
+
+        library.rebuild(vec![
+            guide(1)
+                .steps(vec![
+                    step("<div data-type='quest-block' questname='Dopple' status='start'></div>"),
+                    step("<div data-type='quest-block' questname='Dopple' status='end'></div>"),
+                ])
+                .build(),
+            guide(2)
+                .steps(vec![
+                    step("<p>intro</p>"),
+                    step("<p>farm</p>"),
+                    step("<div data-type='quest-block' questname='Dopple' status='end'></div>"),
+                ])
+                .build(),
+        ]);
+
+        let progresses = vec![Progress {
+            id: 1,
+            current_step: 1,
+            steps: HashMap::new(),
+            updated_at: None,
+        }];
+
+        let completions = index.completions(&library, "dopple", &progresses);
+
+        assert_eq!(completions.len(), 2);
+        assert_eq!(
+            (completions[0].guide_id, completions[0].step_number),
+            (1, 2)
+        );
+        assert!(completions[0].completed);
+        assert_eq!(
+            (completions[1].guide_id, completions[1].step_number),
+            (2, 3)
+        );
+        assert!(!completions[1].completed);
+
+        let quests = index.quests(&library, &progresses);
+
+        assert_eq!(quests.len(), 1);
+        assert!(quests[0].completed);
+        assert_eq!(quests[0].occurrences.len(), 2);
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    guide_library::{GuidesLibrary, LibraryIndex},
+    guides::GuideWithSteps,
+};
+
+// Constants
+
+pub const MAX_SEARCH_RESULTS: usize = 200;
+const SNIPPET_CONTEXT_CHARS: usize = 60;
+
+// Enums
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum GuideSearchField {
+    Name,
+    Description,
+    Step,
+}
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct GuideSearchResult {
+    pub guide_id: u32,
+    pub field: GuideSearchField,
+    pub step_index: Option<u32>,
+    /// HTML-escaped excerpt around the match, the match itself is wrapped in `<mark>`.
+    pub snippet: String,
+}
+
+struct IndexedGuide {
+    name: String,
+    description: String,
+    steps: Vec<String>,
+}
+
+/// In-memory full-text index of the downloaded guides, built from the guides library on the
+/// first search after it changed.
+#[derive(Default)]
+pub struct GuidesSearchIndex {
+    index: LibraryIndex<IndexedGuide>,
+}
+
+// Implementations
+
+impl IndexedGuide {
+    fn from_guide(guide: &GuideWithSteps) -> Self {
+        IndexedGuide {
+            name: guide.name.clone(),
+            description: guide.description.clone().unwrap_or_default(),
+            steps: guide
+                .steps
+                .iter()
+                .map(|step| html_to_plain_text(&step.web_text))
+                .collect(),
+        }
+    }
+}
+
+impl GuidesSearchIndex {
+    /// Search the guides of `library`, nothing is found until it is loaded.
+    pub fn search(
+        &self,
+        library: &GuidesLibrary,
+        query: &str,
+        limit: usize,
+    ) -> Vec<GuideSearchResult> {
+        let query = query.trim();
+
+        if query.is_empty() {
+            return vec![];
+        }
+
+        self.index
+            .with_index(library, IndexedGuide::from_guide, |guides| {
+                search(guides, query, limit)
+            })
+            .unwrap_or_default()
+    }
+}
+
+// Public Functions
+
+pub fn html_to_plain_text(html: &str) -> String {
+    let fragment = scraper::Html::parse_fragment(html);
+
+    fragment
+        .root_element()
+        .text()
+        .flat_map(|text| text.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Private Functions
+
+fn search(
+    guides: &HashMap<u32, IndexedGuide>,
+    query: &str,
+    limit: usize,
+) -> Vec<GuideSearchResult> {
+    let mut guide_ids = guides.keys().copied().collect::<Vec<_>>();
+    guide_ids.sort_unstable();
+
+    let mut results = vec![];
+
+    for guide_id in guide_ids {
+        let guide = &guides[&guide_id];
+
+        if let Some(snippet) = highlight(&guide.name, query) {
+            results.push(GuideSearchResult {
+                guide_id,
+                field: GuideSearchField::Name,
+                step_index: None,
+                snippet,
+            });
+        }
+
+        if let Some(snippet) = highlight(&guide.description, query) {
+            results.push(GuideSearchResult {
+                guide_id,
+                field: GuideSearchField::Description,
+                step_index: None,
+                snippet,
+            });
+        }
+
+        for (step_index, text) in guide.steps.iter().enumerate() {
+            if let Some(snippet) = highlight(text, query) {
+                results.push(GuideSearchResult {
+                    guide_id,
+                    field: GuideSearchField::Step,
+                    step_index: Some(step_index as u32),
+                    snippet,
+                });
+            }
+        }
+
+        if results.len() >= limit {
+            results.truncate(limit);
+            break;
+        }
+    }
+
+    results
+}
+
+/// Case-insensitive search of `needle` in `haystack`, returning the byte range of the first match.
+fn find_case_insensitive(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    let needle = needle.to_lowercase();
+
+    for (start, _) in haystack.char_indices() {
+        let mut expected = needle.chars();
+        let mut end = start;
+        let mut matched = false;
+
+        for (offset, ch) in haystack[start..].char_indices() {
+            let mut lowered = ch.to_lowercase();
+
+            if !lowered.all(|c| expected.next() == Some(c)) {
+                break;
+            }
+
+            end = start + offset + ch.len_utf8();
+
+            if expected.as_str().is_empty() {
+                matched = true;
+                break;
+            }
+        }
+
+        if matched {
+            return Some((start, end));
+        }
+    }
+
+    None
+}
+
+fn highlight(text: &str, query: &str) -> Option<String> {
+    let (start, end) = find_case_insensitive(text, query)?;
+
+    let before = &text[..start];
+    let snippet_start = before
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+
+    let after = &text[end..];
+    let snippet_end = after
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map(|(index, _)| end + index)
+        .unwrap_or(text.len());
+
+    Some(format!(
+        "{}{}<mark>{}</mark>{}{}",
+        if snippet_start > 0 { "…" } else { "" },
+        escape_html(&text[snippet_start..start]),
+        escape_html(&text[start..end]),
+        escape_html(&text[end..snippet_end]),
+        if snippet_end < text.len() { "…" } else { "" },
+    ))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_case_insensitive, highlight, html_to_plain_text};
+
+    #[test]
+    fn html_to_plain_text_strips_tags_and_collapses_whitespace() {
+        let html = "<p>Talk to <b>Dopple   Iop</b></p>\n<p>then <span data-type='custom-tag'>leave</span></p>";
+
+        assert_eq!(html_to_plain_text(html), "Talk to Dopple Iop then leave");
+    }
+
+    #[test]
+    fn find_case_insensitive_returns_byte_range() {
+        assert_eq!(
+            find_case_insensitive("Écaflip et DOPPLE", "dopple"),
+            Some((12, 18))
+        );
+        assert_eq!(find_case_insensitive("écaflip", "ÉCA"), Some((0, 4)));
+        assert_eq!(find_case_insensitive("iop", "cra"), None);
+    }
+
+    #[test]
+    fn highlight_wraps_match_and_escapes_html() {
+        assert_eq!(
+            highlight("Go <here> to Dopple", "dopple"),
+            Some("Go &lt;here&gt; to <mark>Dopple</mark>".to_string())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-const VALID_LINKS: [&'static str; 33] = [
+pub const VALID_LINKS: [&'static str; 33] = [
     "https://dofuspourlesnoobs.com",
     "https://www.dofuspourlesnoobs.com",
     "https://huzounet.fr",
@@ -34,6 +34,19 @@ const VALID_LINKS: [&'static str; 33] = [
     "https://d-bk.net",
 ];
 
+/// Whether a link leads to an allowed domain, only its scheme and host are compared.
+pub fn is_valid_link(link: &str) -> bool {
+    let Ok(url) = tauri::Url::parse(link) else {
+        return false;
+    };
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let origin = format!("{}://{}", url.scheme(), host);
+
+    VALID_LINKS.contains(&origin.as_str())
+}
+
 #[taurpc::procedures(path = "security", export_to = "../src/ipc/bindings.ts")]
 pub trait SecurityApi {
     #[taurpc(alias = "getWhiteList")]
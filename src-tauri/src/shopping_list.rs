@@ -0,0 +1,199 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+
+use crate::guides::{get_installed_guide, Error, GuideStep};
+
+// Constants
+
+/// Quantity written right before a tag, e.g. `10x`, `10 x` or `10`.
+static QUANTITY_BEFORE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+)\s*[xX×]?\s*$").unwrap());
+/// Quantity written right after a tag, e.g. `x10` or `× 10`.
+static QUANTITY_AFTER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*[xX×]\s*(\d+)").unwrap());
+
+// Enums
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, taurpc::specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ShoppingListKind {
+    Item,
+    Monster,
+    Dungeon,
+    WakfuItem,
+}
+
+// Structs
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct ShoppingListEntry {
+    pub kind: ShoppingListKind,
+    pub name: String,
+    pub dofusdb_id: Option<u32>,
+    pub wakfu_id: Option<u32>,
+    /// Sum of the quantities written next to the tags, `None` when none was found.
+    pub quantity: Option<u32>,
+    pub occurrences: u32,
+    pub first_step_index: u32,
+}
+
+// Implementations
+
+impl ShoppingListKind {
+    fn from_tag_type(tag_type: &str) -> Option<Self> {
+        match tag_type {
+            "item" => Some(ShoppingListKind::Item),
+            "monster" => Some(ShoppingListKind::Monster),
+            "dungeon" => Some(ShoppingListKind::Dungeon),
+            "wakfu-item" => Some(ShoppingListKind::WakfuItem),
+            _ => None,
+        }
+    }
+}
+
+impl ShoppingListEntry {
+    fn same_as(&self, other: &ShoppingListEntry) -> bool {
+        if self.kind != other.kind {
+            return false;
+        }
+
+        match (
+            self.dofusdb_id.or(self.wakfu_id),
+            other.dofusdb_id.or(other.wakfu_id),
+        ) {
+            (Some(id), Some(other_id)) => id == other_id,
+            _ => self.name == other.name,
+        }
+    }
+}
+
+// Public Functions
+
+/// Items, monsters and dungeons tagged in the steps of a guide, between `from_step` and
+/// `to_step` included, in the order they first appear.
+pub fn get_guide_shopping_list<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    from_step: Option<u32>,
+    to_step: Option<u32>,
+) -> Result<Vec<ShoppingListEntry>, Error> {
+    let guide = get_installed_guide(app_handle, guide_id)?;
+
+    Ok(build_shopping_list(&guide.steps, from_step, to_step))
+}
+
+// Private Functions
+
+fn build_shopping_list(
+    steps: &[GuideStep],
+    from_step: Option<u32>,
+    to_step: Option<u32>,
+) -> Vec<ShoppingListEntry> {
+    let selector = scraper::Selector::parse("[data-type='custom-tag']").unwrap();
+    let from_step = from_step.unwrap_or(0) as usize;
+    let to_step = to_step.map_or(usize::MAX, |step| step as usize);
+
+    let mut entries: Vec<ShoppingListEntry> = vec![];
+
+    for (step_index, step) in steps.iter().enumerate() {
+        if step_index < from_step || step_index > to_step {
+            continue;
+        }
+
+        let document = scraper::Html::parse_fragment(&step.web_text);
+
+        for element in document.select(&selector) {
+            let attrs = element.value();
+            let Some(kind) = attrs.attr("type").and_then(ShoppingListKind::from_tag_type) else {
+                continue;
+            };
+
+            let parse_id = |name: &str| attrs.attr(name).and_then(|id| id.trim().parse().ok());
+            let entry = ShoppingListEntry {
+                kind,
+                name: attrs
+                    .attr("name")
+                    .map(str::to_string)
+                    .unwrap_or_else(|| crate::search::html_to_plain_text(&element.inner_html())),
+                dofusdb_id: parse_id("dofusdbid"),
+                wakfu_id: parse_id("wakfuid"),
+                quantity: quantity_next_to(&element),
+                occurrences: 1,
+                first_step_index: step_index as u32,
+            };
+
+            match entries.iter_mut().find(|other| other.same_as(&entry)) {
+                Some(other) => {
+                    other.occurrences += 1;
+                    other.quantity = match (other.quantity, entry.quantity) {
+                        (Some(a), Some(b)) => Some(a.saturating_add(b)),
+                        (a, b) => a.or(b),
+                    };
+                }
+                None => entries.push(entry),
+            }
+        }
+    }
+
+    entries
+}
+
+/// Quantity written in the text right before or right after a tag.
+fn quantity_next_to(element: &scraper::ElementRef) -> Option<u32> {
+    let before = element
+        .prev_sibling()
+        .and_then(|node| node.value().as_text().map(|text| text.to_string()))
+        .and_then(|text| {
+            QUANTITY_BEFORE
+                .captures(&text)
+                .and_then(|captures| captures[1].parse().ok())
+        });
+
+    before.or_else(|| {
+        element
+            .next_sibling()
+            .and_then(|node| node.value().as_text().map(|text| text.to_string()))
+            .and_then(|text| {
+                QUANTITY_AFTER
+                    .captures(&text)
+                    .and_then(|captures| captures[1].parse().ok())
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_shopping_list, ShoppingListKind};
+    use crate::test_guides::step;
+
+    #[test]
+    fn build_shopping_list_aggregates_tags_and_quantities() {
+        let steps = vec![
+            step("<p>Buy 10x <span data-type='custom-tag' type='item' name='Wheat' dofusdbid='289'>Wheat</span></p>"),
+            step("<p>Kill <span data-type='custom-tag' type='monster' name='Piou' dofusdbid='489'>Piou</span> x3</p>"),
+            step("<p>Then <span data-type='custom-tag' type='item' name='Wheat' dofusdbid='289'>Wheat</span> x5 and <span data-type='custom-tag' type='quest' name='Q'>Q</span></p>"),
+        ];
+
+        let list = build_shopping_list(&steps, None, None);
+
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].kind, ShoppingListKind::Item);
+        assert_eq!(list[0].dofusdb_id, Some(289));
+        assert_eq!(list[0].quantity, Some(15));
+        assert_eq!(list[0].occurrences, 2);
+        assert_eq!(list[0].first_step_index, 0);
+        assert_eq!(list[1].kind, ShoppingListKind::Monster);
+        assert_eq!(list[1].quantity, Some(3));
+        assert_eq!(list[1].first_step_index, 1);
+
+        let list = build_shopping_list(&steps, Some(1), Some(1));
+
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0].name, "Piou");
+    }
+}
@@ -7,7 +7,7 @@ use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
 use crate::conf::{backup_conf, get_conf, save_conf, Conf, Shortcuts};
 use crate::event::Event;
-use crate::guides::GuidesEventTrigger;
+use crate::guides::{next_step_offset, GuidesEventTrigger};
 
 #[derive(Clone)]
 pub struct ShortcutsCache(Arc<Mutex<Shortcuts>>);
@@ -165,8 +165,16 @@ pub fn handle_shortcuts(app: &App) -> Result<(), Error> {
                                     .expect("[Shortcut] failed to reload webview");
                             } else if shortcut == &go_next_step_sc {
                                 info!("Shortcut {} pressed", shortcut.to_string());
+
+                                // number of steps to move forward, more than 1 when skipping
+                                // the steps of completed quests
+                                let offset = next_step_offset(app_handle).unwrap_or_else(|err| {
+                                    error!("[Shortcut] failed to compute next step: {:?}", err);
+                                    1
+                                });
+
                                 app_handle
-                                    .emit(Event::GoToNextGuideStep.into(), 0)
+                                    .emit(Event::GoToNextGuideStep.into(), offset)
                                     .expect("[Shortcut] failed to emit next event");
                             } else if shortcut == &go_previous_step_sc {
                                 info!("Shortcut {} pressed", shortcut.to_string());
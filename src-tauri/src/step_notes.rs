@@ -4,7 +4,7 @@ use log::{debug, info};
 use serde::Serialize;
 use tauri::{AppHandle, Manager, Runtime};
 
-use crate::tauri_api_ext::StepNotesPathExt;
+use crate::{storage::ReadError, tauri_api_ext::StepNotesPathExt};
 
 // Constants
 
@@ -87,16 +87,11 @@ impl Default for StepNotes {
 pub fn get_step_notes<R: Runtime>(app_handle: &AppHandle<R>) -> Result<StepNotes, Error> {
     let path = app_handle.path().app_step_notes_file();
 
-    let file = fs::read_to_string(path);
-
-    match file {
-        Err(err) => match err.kind() {
-            std::io::ErrorKind::NotFound => Ok(StepNotes::default()),
-            _ => Err(Error::UnhandledIo(err.to_string())),
-        },
-        Ok(file) => {
-            Ok(crate::json::from_str::<StepNotes>(file.as_str()).map_err(Error::Malformed)?)
-        }
+    match crate::storage::read_json::<StepNotes>(&path) {
+        Ok(Some(notes)) => Ok(notes),
+        Ok(None) => Ok(StepNotes::default()),
+        Err(ReadError::Io(err)) => Err(Error::UnhandledIo(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::Malformed(err)),
     }
 }
 
@@ -105,7 +100,7 @@ pub fn save_step_notes<R: Runtime>(notes: &StepNotes, app: &AppHandle<R>) -> Res
 
     let json = crate::json::serialize_pretty(notes).map_err(Error::SerializeStepNotes)?;
 
-    fs::write(path, json).map_err(|err| Error::SaveStepNotes(err.to_string()))
+    crate::storage::write(&path, json).map_err(|err| Error::SaveStepNotes(err.to_string()))
 }
 
 pub fn ensure_step_notes_file(app_handle: &AppHandle) -> Result<(), Error> {
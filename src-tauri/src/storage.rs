@@ -0,0 +1,244 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use log::{debug, warn};
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+// Constants
+
+const BACKUP_EXTENSION: &str = "bak";
+const TEMP_EXTENSION: &str = "tmp";
+
+// Enums
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(String),
+    Malformed(crate::json::Error),
+}
+
+// Public Functions
+
+/// Crash-safe write of a JSON store. The content is written to a temporary file which is synced
+/// then renamed over the store, and the previous content is kept as a `.bak` file when it was
+/// valid JSON.
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if is_valid_json_file(path) {
+        let backup = backup_path(path);
+
+        fs::copy(path, &backup)?;
+        File::open(&backup)?.sync_all()?;
+    }
+
+    write_without_backup(path, contents.as_ref())
+}
+
+/// Read and deserialize a JSON store, recovering it from its backup when it is empty or is not
+/// valid JSON. Returns `None` when the store does not exist, a deleted store is not recovered and
+/// a valid JSON store which does not match `T` is reported as malformed.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, ReadError> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(ReadError::Io(err.to_string())),
+    };
+
+    let error = match crate::json::from_str::<T>(&content) {
+        Ok(value) => return Ok(Some(value)),
+        Err(err) => ReadError::Malformed(err),
+    };
+
+    if serde_json::from_str::<IgnoredAny>(&content).is_ok() {
+        return Err(error);
+    }
+
+    match recover_from_backup(path, |content| crate::json::from_str::<T>(content).ok()) {
+        Some(value) => Ok(Some(value)),
+        None => Err(error),
+    }
+}
+
+/// Read the raw content of a JSON store, recovering it from its backup when it is empty or is not
+/// valid JSON. Returns `None` when the store does not exist.
+pub fn read_to_string(path: &Path) -> Result<Option<String>, ReadError> {
+    read_json::<IgnoredAny>(path)?;
+
+    fs::read_to_string(path)
+        .map(Some)
+        .or_else(|err| match err.kind() {
+            io::ErrorKind::NotFound => Ok(None),
+            _ => Err(ReadError::Io(err.to_string())),
+        })
+}
+
+/// Remove a store along with its backup so it cannot be recovered later.
+pub fn remove(path: &Path) -> io::Result<()> {
+    for file in [backup_path(path), path.to_path_buf()] {
+        match fs::remove_file(file) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Move a store along with its backup.
+pub fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to)?;
+
+    match fs::rename(backup_path(from), backup_path(to)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+
+    sync_parent_dir(to)
+}
+
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_extra_extension(path, BACKUP_EXTENSION)
+}
+
+// Private Functions
+
+fn with_extra_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+
+    file_name.push(".");
+    file_name.push(extension);
+
+    path.with_file_name(file_name)
+}
+
+fn is_valid_json_file(path: &Path) -> bool {
+    fs::read(path)
+        .map(|content| serde_json::from_slice::<IgnoredAny>(&content).is_ok())
+        .unwrap_or(false)
+}
+
+fn write_without_backup(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let temp = with_extra_extension(path, TEMP_EXTENSION);
+
+    {
+        let mut file = File::create(&temp)?;
+
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+
+    fs::rename(&temp, path)?;
+
+    sync_parent_dir(path)
+}
+
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    // directories cannot be opened to be synced on Windows, the rename is durable once it returns
+    Ok(())
+}
+
+fn recover_from_backup<T>(path: &Path, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let backup = backup_path(path);
+    let content = fs::read_to_string(&backup).ok()?;
+    let value = parse(&content)?;
+
+    warn!(
+        "[Storage] {:?} is corrupted, recovering it from {:?}",
+        path, backup
+    );
+
+    match write_without_backup(path, content.as_bytes()) {
+        Ok(()) => debug!("[Storage] {:?} restored from backup", path),
+        Err(err) => warn!("[Storage] failed to restore {:?}: {}", path, err),
+    }
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{backup_path, read_json, write, ReadError};
+
+    #[test]
+    fn write_keeps_previous_valid_content_as_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+
+        write(&path, r#"{"value":1}"#).unwrap();
+        assert!(!backup_path(&path).exists());
+
+        write(&path, r#"{"value":2}"#).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"value":2}"#);
+        assert_eq!(
+            fs::read_to_string(backup_path(&path)).unwrap(),
+            r#"{"value":1}"#
+        );
+    }
+
+    #[test]
+    fn read_json_recovers_truncated_store_from_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+
+        write(&path, r#"{"value":1}"#).unwrap();
+        write(&path, r#"{"value":2}"#).unwrap();
+        fs::write(&path, r#"{"val"#).unwrap();
+
+        let value = read_json::<serde_json::Value>(&path).unwrap().unwrap();
+
+        assert_eq!(value["value"], 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"value":1}"#);
+    }
+
+    #[test]
+    fn read_json_reports_corruption_without_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+
+        fs::write(&path, "").unwrap();
+
+        assert!(matches!(
+            read_json::<serde_json::Value>(&path),
+            Err(ReadError::Malformed(_))
+        ));
+        assert!(matches!(
+            read_json::<serde_json::Value>(&dir.path().join("missing.json")),
+            Ok(None)
+        ));
+    }
+
+    #[test]
+    fn read_json_does_not_recover_deleted_or_mismatching_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+
+        write(&path, r#"{"value":1}"#).unwrap();
+        write(&path, r#"{"value":"two"}"#).unwrap();
+
+        assert!(matches!(
+            read_json::<std::collections::HashMap<String, u32>>(&path),
+            Err(ReadError::Malformed(_))
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"value":"two"}"#);
+
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(read_json::<serde_json::Value>(&path), Ok(None)));
+        assert!(!path.exists());
+    }
+}
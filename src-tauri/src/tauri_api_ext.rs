@@ -5,12 +5,20 @@ use tauri::Runtime;
 
 const APP_CONFIG_FILE: &str = "conf.json";
 const APP_GUIDES_DIR: &str = "guides";
+const APP_GUIDE_VERSIONS_DIR: &str = "guide_versions";
+const APP_GUIDES_QUARANTINE_DIR: &str = "quarantine";
 const APP_RECENT_GUIDES_FILE: &str = "recent_guides.json";
+const APP_GUIDE_HEADERS_FILE: &str = "guide_headers.json";
+const APP_GUIDE_UPDATE_POLICIES_FILE: &str = "guide_update_policies.json";
+const APP_GUIDES_CHANGELOG_FILE: &str = "guides_changelog.json";
 const APP_FIRST_TIME_START_FILE: &str = "first_time_start.json";
 const APP_VIEWED_NOTIFICATIONS_FILE: &str = "viewed_notifications.json";
 const APP_AUTH_FILE: &str = "auth.json";
 const APP_STEP_NOTES_FILE: &str = "step_notes.json";
 const APP_PINNED_GUIDES_FILE: &str = "pinned_guides.json";
+const APP_IMAGE_CACHE_DIR: &str = "images";
+const APP_CATALOG_FILE: &str = "catalog.json";
+const APP_ZAAPS_FILE: &str = "zaaps.json";
 
 pub trait ConfPathExt {
     fn app_conf_file(&self) -> PathBuf;
@@ -19,7 +27,12 @@ pub trait ConfPathExt {
 
 pub trait GuidesPathExt {
     fn app_guides_dir(&self) -> PathBuf;
+    fn app_guide_versions_dir(&self) -> PathBuf;
+    fn app_guides_quarantine_dir(&self) -> PathBuf;
     fn app_recent_guides_file(&self) -> PathBuf;
+    fn app_guide_headers_file(&self) -> PathBuf;
+    fn app_guide_update_policies_file(&self) -> PathBuf;
+    fn app_guides_changelog_file(&self) -> PathBuf;
 }
 
 pub trait FirstTimePathExt {
@@ -42,6 +55,18 @@ pub trait PinnedGuidesPathExt {
     fn app_pinned_guides_file(&self) -> PathBuf;
 }
 
+pub trait ImageCachePathExt {
+    fn app_image_cache_dir(&self) -> PathBuf;
+}
+
+pub trait CatalogPathExt {
+    fn app_catalog_file(&self) -> PathBuf;
+}
+
+pub trait ZaapsPathExt {
+    fn app_zaaps_file(&self) -> PathBuf;
+}
+
 impl<R: Runtime> ConfPathExt for PathResolver<R> {
     fn app_conf_file(&self) -> PathBuf {
         let path = self.app_config_dir().expect("[TauriApi] app_config_file");
@@ -76,6 +101,22 @@ impl<R: Runtime> GuidesPathExt for PathResolver<R> {
         path.join(APP_GUIDES_DIR)
     }
 
+    fn app_guide_versions_dir(&self) -> PathBuf {
+        let path = self
+            .app_config_dir()
+            .expect("[TauriApi] app_guide_versions_dir");
+
+        path.join(APP_GUIDE_VERSIONS_DIR)
+    }
+
+    fn app_guides_quarantine_dir(&self) -> PathBuf {
+        let path = self
+            .app_config_dir()
+            .expect("[TauriApi] app_guides_quarantine_dir");
+
+        path.join(APP_GUIDES_QUARANTINE_DIR)
+    }
+
     fn app_recent_guides_file(&self) -> PathBuf {
         let path = self
             .app_config_dir()
@@ -83,6 +124,30 @@ impl<R: Runtime> GuidesPathExt for PathResolver<R> {
 
         path.join(APP_RECENT_GUIDES_FILE)
     }
+
+    fn app_guide_headers_file(&self) -> PathBuf {
+        let path = self
+            .app_config_dir()
+            .expect("[TauriApi] app_guide_headers_file");
+
+        path.join(APP_GUIDE_HEADERS_FILE)
+    }
+
+    fn app_guide_update_policies_file(&self) -> PathBuf {
+        let path = self
+            .app_config_dir()
+            .expect("[TauriApi] app_guide_update_policies_file");
+
+        path.join(APP_GUIDE_UPDATE_POLICIES_FILE)
+    }
+
+    fn app_guides_changelog_file(&self) -> PathBuf {
+        let path = self
+            .app_config_dir()
+            .expect("[TauriApi] app_guides_changelog_file");
+
+        path.join(APP_GUIDES_CHANGELOG_FILE)
+    }
 }
 
 impl<R: Runtime> FirstTimePathExt for PathResolver<R> {
@@ -131,3 +196,29 @@ impl<R: Runtime> PinnedGuidesPathExt for PathResolver<R> {
         path.join(APP_PINNED_GUIDES_FILE)
     }
 }
+
+impl<R: Runtime> ImageCachePathExt for PathResolver<R> {
+    fn app_image_cache_dir(&self) -> PathBuf {
+        let path = self
+            .app_cache_dir()
+            .expect("[TauriApi] app_image_cache_dir");
+
+        path.join(APP_IMAGE_CACHE_DIR)
+    }
+}
+
+impl<R: Runtime> CatalogPathExt for PathResolver<R> {
+    fn app_catalog_file(&self) -> PathBuf {
+        let path = self.app_cache_dir().expect("[TauriApi] app_catalog_file");
+
+        path.join(APP_CATALOG_FILE)
+    }
+}
+
+impl<R: Runtime> ZaapsPathExt for PathResolver<R> {
+    fn app_zaaps_file(&self) -> PathBuf {
+        let path = self.app_config_dir().expect("[TauriApi] app_zaaps_file");
+
+        path.join(APP_ZAAPS_FILE)
+    }
+}
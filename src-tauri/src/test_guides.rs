@@ -0,0 +1,113 @@
+use crate::guides::{GameType, Guide, GuideLang, GuideStep, GuideWithSteps, Status, User};
+
+// Structs
+
+/// Guide of the tests, a public french Dofus guide without steps until told otherwise.
+pub struct GuideBuilder {
+    guide: GuideWithSteps,
+}
+
+// Implementations
+
+impl GuideBuilder {
+    pub fn steps(mut self, steps: Vec<GuideStep>) -> Self {
+        self.guide.steps = steps;
+        self
+    }
+
+    pub fn game_type(mut self, game_type: GameType) -> Self {
+        self.guide.game_type = game_type;
+        self
+    }
+
+    pub fn likes(mut self, likes: u32) -> Self {
+        self.guide.likes = likes;
+        self
+    }
+
+    pub fn updated_at(mut self, updated_at: &str) -> Self {
+        self.guide.updated_at = Some(updated_at.to_string());
+        self
+    }
+
+    pub fn build(self) -> GuideWithSteps {
+        self.guide
+    }
+
+    /// The guide as listed by the server, without its steps.
+    pub fn summary(self) -> Guide {
+        let guide = self.guide;
+
+        Guide {
+            id: guide.id,
+            name: guide.name,
+            status: guide.status,
+            likes: guide.likes,
+            dislikes: guide.dislikes,
+            downloads: guide.downloads,
+            created_at: String::new(),
+            deleted_at: guide.deleted_at,
+            updated_at: guide.updated_at,
+            lang: guide.lang,
+            game_type: guide.game_type,
+            order: guide.order,
+            user_id: guide.user.id,
+            user: guide.user,
+            description: guide.description,
+            web_description: guide.web_description,
+            node_image: guide.node_image,
+        }
+    }
+}
+
+// Public Functions
+
+pub fn guide(id: u32) -> GuideBuilder {
+    GuideBuilder {
+        guide: GuideWithSteps {
+            id,
+            name: "g".to_string(),
+            description: None,
+            status: Status::Public,
+            likes: 0,
+            dislikes: 0,
+            downloads: None,
+            deleted_at: None,
+            updated_at: None,
+            lang: GuideLang::Fr,
+            game_type: GameType::Dofus,
+            order: 0,
+            user: User {
+                id: 1,
+                name: "u".to_string(),
+                is_admin: 0,
+                is_certified: 0,
+            },
+            web_description: None,
+            node_image: None,
+            steps: vec![],
+            folder: None,
+        },
+    }
+}
+
+/// Step without position.
+pub fn step(web_text: &str) -> GuideStep {
+    GuideStep {
+        name: None,
+        map: None,
+        pos_x: 0,
+        pos_y: 0,
+        web_text: web_text.to_string(),
+    }
+}
+
+/// Step on a map at the given position.
+pub fn step_at(web_text: &str, pos_x: i32, pos_y: i32) -> GuideStep {
+    GuideStep {
+        map: Some("normal".to_string()),
+        pos_x,
+        pos_y,
+        ..step(web_text)
+    }
+}
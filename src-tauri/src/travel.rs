@@ -0,0 +1,338 @@
+use log::{debug, warn};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{
+    conf::{Conf, DEFAULT_TRAVEL_COMMAND_TEMPLATE},
+    guides::{Error, GuideStep},
+    zaap::{
+        get_guide_nearest_zaaps, get_nearest_zaap, get_zaap_plan, reload_zaaps, NearestZaap,
+        StepNearestZaap, Zaap, ZaapPlan,
+    },
+};
+
+// Structs
+
+/// Consecutive steps on the same map, `from_step` and `to_step` are inclusive step indices.
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct TravelStop {
+    pub from_step: u32,
+    pub to_step: u32,
+    pub pos_x: i32,
+    pub pos_y: i32,
+    pub map: Option<String>,
+    pub command: String,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct TravelItinerary {
+    pub guide_id: u32,
+    pub stops: Vec<TravelStop>,
+    /// Travel commands of the stops, one per line.
+    pub text: String,
+}
+
+// Public Functions
+
+/// Travel command of a step built from the template of the conf, `{x}`, `{y}` and `{map}` are
+/// replaced by the position of the step.
+pub fn travel_command(template: &str, step: &GuideStep) -> String {
+    let template = if template.trim().is_empty() {
+        DEFAULT_TRAVEL_COMMAND_TEMPLATE
+    } else {
+        template
+    };
+
+    template
+        .replace("{x}", &step.pos_x.to_string())
+        .replace("{y}", &step.pos_y.to_string())
+        .replace("{map}", step.map.as_deref().unwrap_or_default())
+}
+
+/// Itinerary of the steps between `from_step` and `to_step` included, the consecutive steps on the
+/// same map are collapsed in a single stop.
+pub fn get_itinerary<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+    from_step: Option<u32>,
+    to_step: Option<u32>,
+) -> Result<TravelItinerary, Error> {
+    let guide = crate::guides::get_installed_guide(app_handle, guide_id)?;
+    let template = crate::conf::get_conf(app_handle)
+        .map(|conf| conf.travel_command_template)
+        .map_err(|err| Error::Profile(err.to_string()))?;
+
+    let last_step = (guide.steps.len() as u32).saturating_sub(1);
+    let from_step = from_step.unwrap_or(0);
+    let to_step = to_step.unwrap_or(last_step);
+
+    if guide.steps.is_empty() {
+        return Err(Error::StepOutOfRange(from_step));
+    }
+
+    for step in [from_step, to_step] {
+        if step > last_step {
+            return Err(Error::StepOutOfRange(step));
+        }
+    }
+
+    let stops = build_itinerary(&guide.steps, from_step, to_step, &template);
+    let text = stops
+        .iter()
+        .map(|stop| stop.command.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(TravelItinerary {
+        guide_id,
+        stops,
+        text,
+    })
+}
+
+/// Copy the travel command of the step the profile in use moved to, when `auto_travel_copy` is on
+/// and the step is on another map than the step it left.
+pub fn copy_travel_on_step_change<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    conf: &Conf,
+    previous: &Conf,
+) {
+    if !conf.auto_travel_copy {
+        return;
+    }
+
+    let Some(profile) = conf.profiles.iter().find(|p| p.id == conf.profile_in_use) else {
+        return;
+    };
+    let previous_progresses = previous
+        .profiles
+        .iter()
+        .find(|p| p.id == profile.id)
+        .map(|p| p.progresses.as_slice())
+        .unwrap_or_default();
+
+    let Some((guide_id, from_step, to_step)) = profile.progresses.iter().find_map(|progress| {
+        let previous_step = previous_progresses
+            .iter()
+            .find(|p| p.id == progress.id)
+            .map(|p| p.current_step);
+
+        (previous_step != Some(progress.current_step)).then_some((
+            progress.id,
+            previous_step,
+            progress.current_step,
+        ))
+    }) else {
+        return;
+    };
+
+    let guide = match crate::guides::get_installed_guide(app_handle, guide_id) {
+        Ok(guide) => guide,
+        Err(err) => {
+            debug!("[Travel] cannot read guide {guide_id} to copy its travel: {err}");
+            return;
+        }
+    };
+
+    let Some(step) = guide
+        .steps
+        .get(to_step as usize)
+        .filter(|step| has_position(step))
+    else {
+        return;
+    };
+
+    let on_same_map = from_step
+        .and_then(|from_step| guide.steps.get(from_step as usize))
+        .is_some_and(|from| same_map(from, step));
+
+    if on_same_map {
+        return;
+    }
+
+    let command = travel_command(&conf.travel_command_template, step);
+
+    debug!("[Travel] copying {command}");
+
+    if let Err(err) = app_handle.clipboard().write_text(command) {
+        warn!("[Travel] cannot copy the travel command: {err}");
+    }
+}
+
+/// Stops of the steps between `from_step` and `to_step` included, the consecutive steps on the
+/// same map are collapsed in a single stop and the steps without a position are left out.
+pub fn build_itinerary(
+    steps: &[GuideStep],
+    from_step: u32,
+    to_step: u32,
+    template: &str,
+) -> Vec<TravelStop> {
+    let mut stops: Vec<TravelStop> = vec![];
+    let (from_step, to_step) = (from_step.min(to_step), from_step.max(to_step));
+
+    for index in from_step..=to_step {
+        let Some(step) = steps.get(index as usize) else {
+            break;
+        };
+
+        if !has_position(step) {
+            continue;
+        }
+
+        match stops.last_mut() {
+            Some(stop)
+                if stop.pos_x == step.pos_x && stop.pos_y == step.pos_y && stop.map == step.map =>
+            {
+                stop.to_step = index;
+            }
+            _ => stops.push(TravelStop {
+                from_step: index,
+                to_step: index,
+                pos_x: step.pos_x,
+                pos_y: step.pos_y,
+                map: step.map.clone(),
+                command: travel_command(template, step),
+            }),
+        }
+    }
+
+    stops
+}
+
+/// Whether a step has a position to travel to, the editor leaves the map of the others empty or
+/// sets it to `nomap`.
+pub fn has_position(step: &GuideStep) -> bool {
+    step.map
+        .as_deref()
+        .map(str::trim)
+        .is_some_and(|map| !map.is_empty() && !map.eq_ignore_ascii_case("nomap"))
+}
+
+// Private Functions
+
+fn same_map(a: &GuideStep, b: &GuideStep) -> bool {
+    a.pos_x == b.pos_x && a.pos_y == b.pos_y && a.map == b.map
+}
+
+// TauRPC API
+
+#[taurpc::procedures(path = "travel", export_to = "../src/ipc/bindings.ts")]
+pub trait TravelApi {
+    #[taurpc(alias = "getItinerary")]
+    async fn get_itinerary<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from_step: Option<u32>,
+        to_step: Option<u32>,
+    ) -> Result<TravelItinerary, Error>;
+    #[taurpc(alias = "nearestZaap")]
+    async fn nearest_zaap<R: Runtime>(
+        app_handle: AppHandle<R>,
+        pos_x: i32,
+        pos_y: i32,
+    ) -> Result<Option<NearestZaap>, Error>;
+    #[taurpc(alias = "getGuideNearestZaaps")]
+    async fn get_guide_nearest_zaaps<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<Vec<StepNearestZaap>, Error>;
+    #[taurpc(alias = "getZaapPlan")]
+    async fn get_zaap_plan<R: Runtime>(
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<ZaapPlan, Error>;
+    #[taurpc(alias = "reloadZaaps")]
+    async fn reload_zaaps<R: Runtime>(app_handle: AppHandle<R>) -> Result<Vec<Zaap>, Error>;
+}
+
+#[derive(Clone)]
+pub struct TravelApiImpl;
+
+#[taurpc::resolvers]
+impl TravelApi for TravelApiImpl {
+    async fn get_itinerary<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+        from_step: Option<u32>,
+        to_step: Option<u32>,
+    ) -> Result<TravelItinerary, Error> {
+        get_itinerary(&app_handle, guide_id, from_step, to_step)
+    }
+
+    async fn nearest_zaap<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        pos_x: i32,
+        pos_y: i32,
+    ) -> Result<Option<NearestZaap>, Error> {
+        Ok(get_nearest_zaap(&app_handle, pos_x, pos_y))
+    }
+
+    async fn get_guide_nearest_zaaps<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<Vec<StepNearestZaap>, Error> {
+        get_guide_nearest_zaaps(&app_handle, guide_id)
+    }
+
+    async fn get_zaap_plan<R: Runtime>(
+        self,
+        app_handle: AppHandle<R>,
+        guide_id: u32,
+    ) -> Result<ZaapPlan, Error> {
+        get_zaap_plan(&app_handle, guide_id)
+    }
+
+    async fn reload_zaaps<R: Runtime>(self, app_handle: AppHandle<R>) -> Result<Vec<Zaap>, Error> {
+        reload_zaaps(&app_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_itinerary, travel_command};
+    use crate::{
+        guides::GuideStep,
+        test_guides::{step, step_at},
+    };
+
+    #[test]
+    fn build_itinerary_collapses_consecutive_steps_on_the_same_map() {
+        let steps = vec![
+            step_at("", 4, -18),
+            step_at("", 4, -18),
+            step_at("", -2, 0),
+            step_at("", 4, -18),
+        ];
+
+        let stops = build_itinerary(&steps, 0, 3, "/travel {x},{y}");
+
+        assert_eq!(stops.len(), 3);
+        assert_eq!((stops[0].from_step, stops[0].to_step), (0, 1));
+        assert_eq!(stops[1].command, "/travel -2,0");
+        assert_eq!(stops[2].from_step, 3);
+        assert_eq!(build_itinerary(&steps, 1, 2, "").len(), 2);
+        assert_eq!(travel_command("  ", &steps[0]), "/travel 4,-18");
+        assert_eq!(travel_command("/t {x} {y}", &steps[2]), "/t -2 0");
+    }
+
+    #[test]
+    fn build_itinerary_skips_steps_without_position() {
+        let nomap = GuideStep {
+            map: Some("nomap".to_string()),
+            ..step("")
+        };
+        let steps = vec![step_at("", 4, -18), step(""), nomap, step_at("", 4, -18)];
+
+        let stops = build_itinerary(&steps, 0, 3, "");
+
+        assert_eq!(stops.len(), 1);
+        assert_eq!((stops[0].from_step, stops[0].to_step), (0, 3));
+    }
+}
@@ -0,0 +1,356 @@
+use std::{path::Path, sync::Mutex};
+
+use log::{info, warn};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::{
+    guides::{Error, GameType, GuideStep, GuideWithSteps},
+    storage::ReadError,
+    tauri_api_ext::ZaapsPathExt,
+};
+
+// Constants
+
+/// Zaaps of the World of Twelve, replaced by the content of `zaaps.json` in the config dir when
+/// the file exists.
+const DEFAULT_ZAAPS: &[(&str, i32, i32)] = &[
+    ("Astrub", 5, -18),
+    ("Amakna Village", -2, 0),
+    ("Amakna Castle", 3, -5),
+    ("Madrestam Harbour", 7, -4),
+    ("Gobball Corner", 5, 7),
+    ("Crackler Mountain", -5, -8),
+    ("Edge of the Evil Forest", -1, 13),
+    ("Scaraleaf Plain", -1, 24),
+    ("Sufokia", 13, 26),
+    ("Brâkmar", -26, 35),
+    ("Bonta", -32, -56),
+    ("Sidimote Moors", -25, 12),
+    ("Imp Village", -16, 1),
+    ("Rocky Roads", -20, -20),
+    ("Cania Massif", -13, -28),
+    ("Cania Fields", -27, -36),
+    ("Lake Cania", -3, -42),
+    ("Rocky Plains", -17, -47),
+    ("Kanig Village", 0, -56),
+    ("Dunes of Bones", 15, -58),
+    ("Coastal Village", -46, 18),
+    ("Zoth Village", -53, 18),
+    ("Frigost Village", -78, -41),
+];
+
+// Structs
+
+#[derive(Debug, PartialEq)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct Zaap {
+    pub name: String,
+    pub pos_x: i32,
+    pub pos_y: i32,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct NearestZaap {
+    pub zaap: Zaap,
+    /// Manhattan distance in maps between the zaap and the position.
+    pub distance: u32,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct StepNearestZaap {
+    pub step: u32,
+    pub nearest: Option<NearestZaap>,
+}
+
+/// Move between two consecutive stops of a guide, through the zaaps near them when it is shorter
+/// than walking.
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct ZaapPlanLeg {
+    pub from_step: u32,
+    pub to_step: u32,
+    pub walking_distance: u32,
+    /// Zaap to walk to from the previous stop, `None` when walking is shorter.
+    pub departure_zaap: Option<Zaap>,
+    pub arrival_zaap: Option<Zaap>,
+    /// Maps walked following the plan.
+    pub distance: u32,
+}
+
+#[derive(Debug)]
+#[taurpc::ipc_type]
+#[serde(rename_all = "camelCase")]
+pub struct ZaapPlan {
+    pub guide_id: u32,
+    /// Zaap to start the guide from.
+    pub start: Option<NearestZaap>,
+    pub legs: Vec<ZaapPlanLeg>,
+    pub walking_distance: u32,
+    pub distance: u32,
+}
+
+/// Zaaps loaded on first use, from the data file of the config dir or the defaults.
+#[derive(Default)]
+pub struct ZaapTable {
+    zaaps: Mutex<Option<Vec<Zaap>>>,
+}
+
+// Implementations
+
+impl ZaapTable {
+    fn with_zaaps<T>(&self, file: &Path, f: impl FnOnce(&[Zaap]) -> T) -> T {
+        let mut lock = self.zaaps.lock().unwrap();
+        let zaaps = lock.get_or_insert_with(|| {
+            read_zaaps(file).unwrap_or_else(|err| {
+                warn!("[Zaap] {}, using the default zaaps", err);
+                default_zaaps()
+            })
+        });
+
+        f(zaaps)
+    }
+}
+
+// Public Functions
+
+pub fn nearest_zaap(zaaps: &[Zaap], pos_x: i32, pos_y: i32) -> Option<NearestZaap> {
+    zaaps
+        .iter()
+        .map(|zaap| (zaap, distance((zaap.pos_x, zaap.pos_y), (pos_x, pos_y))))
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(zaap, distance)| NearestZaap {
+            zaap: zaap.clone(),
+            distance,
+        })
+}
+
+pub fn get_nearest_zaap<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    pos_x: i32,
+    pos_y: i32,
+) -> Option<NearestZaap> {
+    let file = app_handle.path().app_zaaps_file();
+
+    app_handle
+        .state::<ZaapTable>()
+        .with_zaaps(&file, |zaaps| nearest_zaap(zaaps, pos_x, pos_y))
+}
+
+/// Nearest zaap of every step of a guide, `None` for the steps without a position. Empty for the
+/// Wakfu guides, the zaaps are the ones of Dofus.
+pub fn get_guide_nearest_zaaps<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<Vec<StepNearestZaap>, Error> {
+    let guide = crate::guides::get_installed_guide(app_handle, guide_id)?;
+    let file = app_handle.path().app_zaaps_file();
+
+    Ok(app_handle.state::<ZaapTable>().with_zaaps(&file, |zaaps| {
+        steps_nearest_zaaps(zaap_steps(&guide), zaaps)
+    }))
+}
+
+/// Plan of the travels of a guide through the zaaps, empty for the Wakfu guides.
+pub fn get_zaap_plan<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    guide_id: u32,
+) -> Result<ZaapPlan, Error> {
+    let guide = crate::guides::get_installed_guide(app_handle, guide_id)?;
+    let file = app_handle.path().app_zaaps_file();
+
+    Ok(app_handle
+        .state::<ZaapTable>()
+        .with_zaaps(&file, |zaaps| plan(guide_id, zaap_steps(&guide), zaaps)))
+}
+
+/// Read the zaaps data file again, the defaults are used when it does not exist.
+pub fn reload_zaaps<R: Runtime>(app_handle: &AppHandle<R>) -> Result<Vec<Zaap>, Error> {
+    let zaaps = read_zaaps(&app_handle.path().app_zaaps_file())?;
+
+    info!("[Zaap] loaded {} zaaps", zaaps.len());
+
+    *app_handle.state::<ZaapTable>().zaaps.lock().unwrap() = Some(zaaps.clone());
+
+    Ok(zaaps)
+}
+
+// Private Functions
+
+fn default_zaaps() -> Vec<Zaap> {
+    DEFAULT_ZAAPS
+        .iter()
+        .map(|(name, pos_x, pos_y)| Zaap {
+            name: name.to_string(),
+            pos_x: *pos_x,
+            pos_y: *pos_y,
+        })
+        .collect()
+}
+
+fn read_zaaps(file: &Path) -> Result<Vec<Zaap>, Error> {
+    match crate::storage::read_json::<Vec<Zaap>>(file) {
+        Ok(zaaps) => Ok(zaaps.unwrap_or_else(default_zaaps)),
+        Err(ReadError::Io(err)) => Err(Error::ReadZaaps(err)),
+        Err(ReadError::Malformed(err)) => Err(Error::ReadZaaps(err.to_string())),
+    }
+}
+
+/// Steps of a guide to travel through the zaaps, none for the Wakfu guides since the zaaps are the
+/// ones of Dofus.
+fn zaap_steps(guide: &GuideWithSteps) -> &[GuideStep] {
+    match guide.game_type {
+        GameType::Dofus => &guide.steps,
+        GameType::Wakfu => &[],
+    }
+}
+
+fn steps_nearest_zaaps(steps: &[GuideStep], zaaps: &[Zaap]) -> Vec<StepNearestZaap> {
+    steps
+        .iter()
+        .enumerate()
+        .map(|(index, step)| StepNearestZaap {
+            step: index as u32,
+            nearest: crate::travel::has_position(step)
+                .then(|| nearest_zaap(zaaps, step.pos_x, step.pos_y))
+                .flatten(),
+        })
+        .collect()
+}
+
+/// Manhattan distance in maps.
+fn distance(a: (i32, i32), b: (i32, i32)) -> u32 {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+fn plan(guide_id: u32, steps: &[GuideStep], zaaps: &[Zaap]) -> ZaapPlan {
+    let last_step = (steps.len() as u32).saturating_sub(1);
+    let stops = crate::travel::build_itinerary(steps, 0, last_step, "");
+    let start = stops
+        .first()
+        .and_then(|stop| nearest_zaap(zaaps, stop.pos_x, stop.pos_y));
+
+    let legs = stops
+        .windows(2)
+        .map(|stops| {
+            let (from, to) = (&stops[0], &stops[1]);
+            let walking_distance = distance((from.pos_x, from.pos_y), (to.pos_x, to.pos_y));
+            let departure = nearest_zaap(zaaps, from.pos_x, from.pos_y);
+            let arrival = nearest_zaap(zaaps, to.pos_x, to.pos_y);
+
+            let mut leg = ZaapPlanLeg {
+                from_step: from.to_step,
+                to_step: to.from_step,
+                walking_distance,
+                departure_zaap: None,
+                arrival_zaap: None,
+                distance: walking_distance,
+            };
+
+            if let (Some(departure), Some(arrival)) = (departure, arrival) {
+                let zaap_distance = departure.distance + arrival.distance;
+
+                if departure.zaap != arrival.zaap && zaap_distance < walking_distance {
+                    leg.departure_zaap = Some(departure.zaap);
+                    leg.arrival_zaap = Some(arrival.zaap);
+                    leg.distance = zaap_distance;
+                }
+            }
+
+            leg
+        })
+        .collect::<Vec<_>>();
+
+    ZaapPlan {
+        guide_id,
+        start,
+        walking_distance: legs.iter().map(|leg| leg.walking_distance).sum(),
+        distance: legs.iter().map(|leg| leg.distance).sum(),
+        legs,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_zaaps, nearest_zaap, plan, steps_nearest_zaaps, zaap_steps};
+    use crate::{
+        guides::{GameType, GuideStep},
+        test_guides::{guide, step, step_at},
+    };
+
+    #[test]
+    fn plan_takes_zaaps_only_when_walking_is_longer() {
+        let zaaps = default_zaaps();
+        let nearest = nearest_zaap(&zaaps, 4, -16).unwrap();
+
+        assert_eq!(nearest.zaap.name, "Astrub");
+        assert_eq!(nearest.distance, 3);
+
+        // Astrub, two maps away, then Sufokia
+        let plan = plan(
+            1,
+            &[
+                step_at("", 5, -17),
+                step_at("", 5, -16),
+                step_at("", 13, 25),
+            ],
+            &zaaps,
+        );
+
+        assert_eq!(plan.legs.len(), 2);
+        assert!(plan.legs[0].departure_zaap.is_none());
+        assert_eq!(plan.legs[0].distance, 1);
+        assert_eq!(plan.legs[1].arrival_zaap.as_ref().unwrap().name, "Sufokia");
+        assert_eq!(plan.legs[1].distance, 2 + 1);
+        assert_eq!(plan.walking_distance, 1 + 49);
+        assert_eq!(plan.distance, 1 + 3);
+    }
+
+    #[test]
+    fn plan_skips_steps_without_position() {
+        let nomap = GuideStep {
+            map: Some("nomap".to_string()),
+            ..step("")
+        };
+
+        let plan = plan(
+            1,
+            &[step_at("", 5, -17), nomap, step_at("", 5, -16)],
+            &default_zaaps(),
+        );
+
+        assert_eq!(plan.legs.len(), 1);
+        assert_eq!((plan.legs[0].from_step, plan.legs[0].to_step), (0, 2));
+        assert_eq!(plan.walking_distance, 1);
+    }
+
+    #[test]
+    fn nearest_zaaps_leave_out_wakfu_guides_and_steps_without_position() {
+        let zaaps = default_zaaps();
+        let steps = vec![
+            step_at("", 4, -16),
+            step(""),
+            GuideStep {
+                map: Some("nomap".to_string()),
+                ..step_at("", 4, -16)
+            },
+        ];
+        let dofus = guide(1).steps(steps.clone()).build();
+        let wakfu = guide(2).game_type(GameType::Wakfu).steps(steps).build();
+
+        let nearest = steps_nearest_zaaps(zaap_steps(&dofus), &zaaps);
+
+        assert_eq!(nearest.len(), 3);
+        assert_eq!(nearest[0].nearest.as_ref().unwrap().zaap.name, "Astrub");
+        assert!(nearest[1].nearest.is_none());
+        assert!(nearest[2].nearest.is_none());
+
+        assert!(zaap_steps(&wakfu).is_empty());
+        assert!(plan(2, zaap_steps(&wakfu), &zaaps).legs.is_empty());
+    }
+}
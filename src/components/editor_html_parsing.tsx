@@ -92,8 +92,12 @@ export function EditorHtmlParsing({
                   disabled={disabled}
                   id={`copy-position-${posX}-${posY}`}
                   onClick={async () => {
-                    await copyPosition(Number.parseInt(posX, 10), Number.parseInt(posY, 10), conf.data.autoTravelCopy)
-                    const content = conf.data.autoTravelCopy ? `/travel ${posX},${posY}` : `[${posX},${posY}]`
+                    const content = await copyPosition(
+                      Number.parseInt(posX, 10),
+                      Number.parseInt(posY, 10),
+                      conf.data.autoTravelCopy,
+                      conf.data.travelCommandTemplate,
+                    )
                     toast(t`${content} copié`)
                   }}
                   title={conf.data.autoTravelCopy ? 'Copier la commande autopilote' : 'Copier la position'}
@@ -71,7 +71,17 @@ export function StepProgress({
   }
 
   useWebviewEvent('go-to-previous-guide-step', () => void onPrevious(), [currentIndex])
-  useWebviewEvent('go-to-next-guide-step', () => void onNext(), [currentIndex])
+  useWebviewEvent(
+    'go-to-next-guide-step',
+    ({ payload: offset }) => {
+      if (offset !== null && offset > 1) {
+        void onChangeStep(Math.min(maxIndex, currentIndex + offset))
+      } else {
+        void onNext()
+      }
+    },
+    [currentIndex, maxIndex],
+  )
 
   return (
     <div className="flex min-w-0 flex-1 items-center gap-1" onWheel={handleWheel}>
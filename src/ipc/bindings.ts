@@ -16,7 +16,50 @@ export type AuthTokens = { access_token: string; refresh_token: string | null; e
 
 export type AutoPilot = { name: string; position: string }
 
-export type Conf = { autoTravelCopy: boolean; showDoneGuides: boolean; lang?: ConfLang; theme?: ConfTheme; fontSize?: FontSize; guideDisplay?: GuideDisplay; profiles: Profile[]; profileInUse: string; autoPilots: AutoPilot[]; notes: Note[]; opacity: number; autoOpenGuides?: boolean; shortcuts?: Shortcuts }
+export type AvailableGuideUpdate = { id: number; name: string;
+/**
+ * `updated_at` of the guide on the server.
+ */
+updatedAt: string | null }
+
+/**
+ * What to do when a guide of the bundle is already installed.
+ */
+export type BundleConflictStrategy =
+/**
+ * Keep the installed guide.
+ */
+"skip" |
+/**
+ * Replace the installed guide with the one of the bundle.
+ */
+"replace" |
+/**
+ * Keep whichever guide was updated last.
+ */
+"keepNewest"
+
+export type BundleExport = { path: string; guidesCount: number }
+
+export type BundleImport = { imported: number[]; replaced: number[]; skipped: number[] }
+
+export type Conf = { autoTravelCopy: boolean; showDoneGuides: boolean; lang?: ConfLang; theme?: ConfTheme; fontSize?: FontSize; guideDisplay?: GuideDisplay; profiles: Profile[]; profileInUse: string; autoPilots: AutoPilot[]; notes: Note[]; opacity: number; autoOpenGuides?: boolean; shortcuts?: Shortcuts;
+/**
+ * Number of previous versions kept for each guide, 0 disables the history.
+ */
+guideVersionsRetention?: number;
+/**
+ * Whether the next step shortcut jumps over the steps of quests already completed.
+ */
+skipCompletedSteps?: boolean;
+/**
+ * Size of the guide images cache on disk, the least recently used images are evicted first.
+ */
+imageCacheMaxSizeMb?: number;
+/**
+ * Travel command of a step, `{x}`, `{y}` and `{map}` are replaced by its position.
+ */
+travelCommandTemplate?: string }
 
 export type ConfError = { Malformed: JsonError } | { CreateConfDir: string } | { ConfDir: string } | { SerializeConf: JsonError } | { UnhandledIo: string } | { SaveConf: string } | "GetProfileInUse" | { ResetConf: ConfError }
 
@@ -34,32 +77,212 @@ export type GameType = "dofus" | "wakfu"
 
 export type Guide = { id: number; name: string; status: Status; likes: number; dislikes: number; downloads: number | null; created_at: string; deleted_at: string | null; updated_at: string | null; lang: GuideLang; game_type?: GameType; order: number; user: GuideUser; user_id: number; description: string | null; web_description: string | null; node_image: string | null }
 
+export type GuideCatalog = { guides: Guide[];
+/**
+ * The server could not be reached, the guides are the ones of the last successful request.
+ */
+stale: boolean; fetchedAt: string;
+/**
+ * Guides missing from the catalog when it was last visited, empty before the first visit.
+ */
+newGuideIds: number[];
+/**
+ * Guides whose `updated_at` changed since the catalog was last visited.
+ */
+updatedGuideIds: number[] }
+
+/**
+ * Steps changed by the update of a guide, `added` and `modified` are step indices in the new
+ * version and `removed` step indices in the previous one.
+ */
+export type GuideChangelogEntry = { guideId: number; name: string; previousUpdatedAt: string | null; updatedAt: string | null; added: number[]; removed: number[]; modified: number[] }
+
+export type GuideDependencies = { links: GuideLink[];
+/**
+ * Linked guides which are not downloaded, sorted by id.
+ */
+missingGuideIds: number[] }
+
 export type GuideDisplay = "Dynamic" | "Small"
 
+/**
+ * What listing views need from a guide, without its steps.
+ */
+export type GuideHeader = { id: number; name: string; status: Status; lang: GuideLang; game_type?: GameType; order: number; updated_at: string | null; node_image: string | null; steps_count: number }
+
+export type GuideHeaderOrFolder = ({ type: "guide" } & GuideHeader) | ({ type: "folder" } & Folder)
+
 export type GuideLang = "en" | "fr" | "es" | "pt"
 
+/**
+ * A `guide-step` node of a step pointing to another guide.
+ */
+export type GuideLink = { fromGuideId: number; fromStepIndex: number; toGuideId: number;
+/**
+ * Step number of the linked guide as written in the link, starting at 1.
+ */
+toStepNumber: number | null }
+
+export type GuideLintReport = { guideId: number;
+/**
+ * Only the steps with at least one issue, in order.
+ */
+steps: StepLintReport[]; errors: number; warnings: number }
+
 export type GuideOrFolderToDelete = { type: "guide"; id: number; folder: string | null } | { type: "folder"; folder: string }
 
+/**
+ * Filters, sort and page of a catalog query, every filter is optional.
+ */
+export type GuideQuery = { status: Status | null; lang: GuideLang | null; gameType: GameType | null; userId: number | null;
+/**
+ * Case-insensitive text searched in the guide names.
+ */
+search: string | null; sort: GuideSort | null;
+/**
+ * Starts at 1.
+ */
+page: number;
+/**
+ * Guides per page, 50 when 0.
+ */
+perPage: number }
+
+export type GuideRewards = { guideId: number;
+/**
+ * Level of the profile in use the rewards are estimated for.
+ */
+level: number;
+/**
+ * Steps completing at least one quest.
+ */
+steps: StepRewards[]; experience: number; kamas: number;
+/**
+ * Completed quests not found on DofusDB, they are left out of the totals.
+ */
+unresolvedQuests: string[] }
+
+export type GuideRollback = { guide: GuideWithSteps;
+/**
+ * Update policy of the guide after the rollback, a guide updated automatically is frozen so
+ * the next update does not undo the rollback.
+ */
+updatePolicy: UpdatePolicy }
+
+export type GuideSearchField = "name" | "description" | "step"
+
+export type GuideSearchResult = { guideId: number; field: GuideSearchField; stepIndex: number | null;
+/**
+ * HTML-escaped excerpt around the match, the match itself is wrapped in `<mark>`.
+ */
+snippet: string }
+
+/**
+ * Last guide opened by a profile, to resume it where it was left.
+ */
+export type GuideSession = { guide: GuideWithSteps; step: number; progress: Progress | null; lastOpenedAt: string | null; timeSpentSecs: number }
+
+export type GuideSort =
+/**
+ * Most liked first.
+ */
+"likes" |
+/**
+ * Most downloaded first.
+ */
+"downloads" |
+/**
+ * Most recently updated first.
+ */
+"updatedAt" |
+/**
+ * Ascending `order`, as ordered by the authors.
+ */
+"order"
+
 export type GuideStep = { name: string | null; map: string | null; pos_x: number; pos_y: number; web_text: string }
 
 export type GuideStepNotes = { steps: Partial<{ [key in number]: StepNote }> }
 
+/**
+ * Update policy of the guides not updated automatically, by guide id.
+ */
+export type GuideUpdatePolicies = { guides: Partial<{ [key in number]: UpdatePolicy }> }
+
 export type GuideUser = { id: number; name: string; is_admin: number; is_certified: number }
 
+export type GuideVersion = { version: string; savedAt: string; updatedAt: string | null; stepsCount: number }
+
 export type GuideWithSteps = { id: number; name: string; description: string | null; status: Status; likes: number; dislikes: number; downloads: number | null; deleted_at: string | null; updated_at: string | null; lang: GuideLang; game_type?: GameType; order: number; user: GuideUser; web_description: string | null; node_image: string | null; steps: GuideStep[] }
 
 export type Guides = { guides: GuideWithSteps[] }
 
-export type GuidesError = { Pattern: string } | { ReadGuidesDirGlob: string } | { ReadGuideFile: string } | { ReadRecentGuidesFile: string } | { GuideMalformed: JsonError } | { GuideNotFound: number } | { RecentGuidesFileMalformed: string } | { SerializeGuide: JsonError } | { SerializeRecentGuidesFile: JsonError } | { CreateGuidesDir: string } | { WriteGuideFile: string } | { WriteRecentGuidesFile: string } | { RequestGuide: string } | { RequestGuideContent: string } | { RequestGuides: string } | { RequestGuidesContent: string } | { GuideWithStepsMalformed: JsonError } | { GuidesMalformed: JsonError } | { ReadGuidesDir: string } | { GetGuideInSystem: number } | { DeleteGuideFileInSystem: string } | { DeleteGuideFolderInSystem: string } | { Opener: string } | "NetworkUnavailable"
+export type GuidesChangelog = { createdAt: string; guides: GuideChangelogEntry[] }
+
+export type GuidesDownloadProgress = { done: number; total: number }
+
+export type GuidesError = { Pattern: string } | { ReadGuidesDirGlob: string } | { ReadGuideFile: string } | { ReadRecentGuidesFile: string } | { GuideMalformed: JsonError } | { GuideNotFound: number } | { RecentGuidesFileMalformed: string } | { SerializeGuide: JsonError } | { SerializeRecentGuidesFile: JsonError } | { CreateGuidesDir: string } | { WriteGuideFile: string } | { WriteRecentGuidesFile: string } | { RequestGuide: string } | { RequestGuideContent: string } | { RequestGuides: string } | { RequestGuidesContent: string } | { GuideWithStepsMalformed: JsonError } | { GuidesMalformed: JsonError } | { ReadGuidesDir: string } | { GetGuideInSystem: number } | { DeleteGuideFileInSystem: string } | { DeleteGuideFolderInSystem: string } | { Opener: string } | "NetworkUnavailable" | { WriteGuideVersion: string } | { ReadGuideVersion: string } | { GuideVersionNotFound: string } | { QuarantineGuideFile: string } | { ReadQuarantine: string } | { QuarantinedGuideNotFound: string } | { QuarantinedGuideUnrecoverable: string } | { PurgeQuarantine: string } | { InvalidFolder: string } | { ReadBundle: string } | { WriteBundle: string } | { BundleMalformed: JsonError } | { UnsupportedBundleVersion: number } | { Profile: string } | { NotALocalGuide: number } | "LocalGuideIdsExhausted" | { StepOutOfRange: number } | { FolderNotFound: string } | { FolderAlreadyExists: string } | { CreateFolder: string } | { MoveInSystem: string } | { ReadUpdatePolicies: string } | { WriteUpdatePolicies: string } | { ReadChangelog: string } | { WriteChangelog: string } | { ReadZaaps: string }
 
 export type GuidesOrFolder = ({ type: "guide" } & GuideWithSteps) | ({ type: "folder" } & Folder)
 
-export type ImageError = { RequestImage: string } | { ConvertToBytes: string }
+export type GuidesPage = { guides: Guide[]; page: number; perPage: number;
+/**
+ * Guides matching the query across every page, when known.
+ */
+total: number | null; hasMore: boolean;
+/**
+ * The server could not be reached, the guides come from the last fetched catalog.
+ */
+stale: boolean }
+
+export type ImageError = { RequestImage: string } | { ConvertToBytes: string } | { CacheImage: string } | { ImageLargerThanCache: number }
+
+export type IndexedQuest = { name: string; occurrences: QuestOccurrence[];
+/**
+ * Whether the active profile went through a step completing the quest in one of its guides.
+ */
+completed: boolean }
 
 export type IsOld = { from: string; to: string; isOld: boolean }
 
 export type JsonError = { Json: string } | { Serialize: string }
 
+export type LinkedGuidesDownload = { downloaded: number[]; failed: Partial<{ [key in number]: string }> }
+
+export type LintIssue = { kind: LintIssueKind; severity: LintSeverity;
+/**
+ * Offending value, like the url of an image or the status of a quest block.
+ */
+detail: string | null }
+
+export type LintIssueKind =
+/**
+ * The step has no map, its position is not displayed.
+ */
+"missingPosition" | "implausiblePosition" | "emptyStep" |
+/**
+ * A `guide-step` link without guide id, or pointing to a step the guide does not have.
+ */
+"brokenGuideStepLink" |
+/**
+ * A `guide-step` link to a guide which is not downloaded, its step cannot be checked.
+ */
+"uncheckedGuideStepLink" | "brokenImageUrl" | "unreachableImage" |
+/**
+ * A link to a domain outside of the allowed ones, it is displayed as plain text.
+ */
+"linkNotAllowed" | "unknownQuestStatus" | "missingQuestName"
+
+export type LintSeverity = "warning" | "error"
+
+export type MalformedGuidesReport = { recovered: QuarantinedGuideFile[]; failed: QuarantinedGuideFile[] }
+
+export type NearestZaap = { zaap: Zaap;
+/**
+ * Manhattan distance in maps between the zaap and the position.
+ */
+distance: number }
+
 export type Note = { name: string; text: string }
 
 export type Notification = { id: number; text: string; displayAt: string; createdAt: string; updatedAt: string }
@@ -68,13 +291,28 @@ export type NotificationsError = { Malformed: JsonError } | { SerializeViewedNot
 
 export type OAuthError = { OpenBrowser: string } | { SaveAuth: string } | { LoadAuth: string } | { CleanAuth: string } | { Json: JsonError } | { TokenExchange: string } | { InvalidTokenResponse: string }
 
+export type OfflineGuideImages = {
+/**
+ * Images of the guide now in the cache, including the ones which already were.
+ */
+cached: number;
+/**
+ * Urls which could not be downloaded with the reason.
+ */
+failed: Partial<{ [key in string]: string }> }
+
 export type OpenGuideStep = { step: number; progressionStep: number | null }
 
 export type PinnedGuides = { profiles: Partial<{ [key in string]: ProfilePinnedGuides }> }
 
 export type PinnedGuidesError = { Malformed: JsonError } | { CreateDir: string } | { ConfDir: string } | { SerializePinnedGuides: JsonError } | { UnhandledIo: string } | { SavePinnedGuides: string } | "LimitReached"
 
-export type Profile = { id: string; name: string; level?: number; progresses: Progress[]; server_id?: number | null }
+export type Profile = { id: string; name: string; level?: number; progresses: Progress[]; server_id?: number | null;
+/**
+ * Names of the quests the profile completed, recorded when passing the step ending them.
+ * Only edited through `setQuestCompleted`, the value sent with `set` is ignored.
+ */
+completed_quests?: string[] }
 
 export type ProfilePinnedGuides = { guides: number[] }
 
@@ -82,10 +320,31 @@ export type ProfileStepNotes = { guides: Partial<{ [key in number]: GuideStepNot
 
 export type Progress = { id: number; currentStep: number; steps: Partial<{ [key in number]: ConfStep }>; updatedAt?: string | null }
 
+/**
+ * A malformed guide file moved out of the guides directory.
+ */
+export type QuarantinedGuideFile = { entry: string; id: number | null; fileName: string; quarantinedAt: string }
+
+export type QuestCompletion = { guideId: number; stepNumber: number; completed: boolean }
+
 export type QuestError = { RequestQuest: string } | { RequestQuestContent: string } | { DofusDbQuestMalformed: JsonError }
 
+export type QuestOccurrence = { guideId: number;
+/**
+ * Step numbers start at 1, like in the guide summary.
+ */
+statuses: SummaryQuestStatus[] }
+
+export type QuestRewards = { name: string; questId: number; experience: number; kamas: number }
+
 export type QuestSummary = { name: string; statuses: SummaryQuestStatus[] }
 
+/**
+ * Steps of a guide which could not be matched after an update, the progress and notes attached
+ * to them have been moved to the closest previous step.
+ */
+export type RemappedGuideSteps = { guideId: number; unmappedSteps: number[] }
+
 export type RemoteProfile = { id: number; uuid: string | null; name: string; progresses: SyncProgressPayload[] }
 
 export type RemovedGuideFile = { id: number | null; file_name: string }
@@ -94,18 +353,41 @@ export type ReportError = { Server: string } | { Status: [number, string] } | "N
 
 export type ReportPayload = { username: string | null; content: string; step: number; guide_id: number }
 
+export type ShoppingListEntry = { kind: ShoppingListKind; name: string; dofusdbId: number | null; wakfuId: number | null;
+/**
+ * Sum of the quantities written next to the tags, `None` when none was found.
+ */
+quantity: number | null; occurrences: number; firstStepIndex: number }
+
+export type ShoppingListKind = "item" | "monster" | "dungeon" | "wakfuItem"
+
 export type ShortcutError = { Register: string } | { RegisterPlugin: string } | { ParseShortcut: string } | { GetConf: ConfError } | { Unregister: string }
 
 export type Shortcuts = { resetConf?: string; goNextStep?: string; goPreviousStep?: string; copyCurrentStep?: string }
 
 export type Status = "draft" | "public" | "private" | "certified" | "gp"
 
+/**
+ * Change of a step between two versions of a guide, `from` is the step index in the old version
+ * and `to` the step index in the new one.
+ */
+export type StepChange = { type: "unchanged"; from: number; to: number } | { type: "modified"; from: number; to: number } | { type: "added"; to: number } | { type: "removed"; from: number }
+
+export type StepLintReport = { stepIndex: number; issues: LintIssue[] }
+
+export type StepNearestZaap = { step: number; nearest: NearestZaap | null }
+
 export type StepNote = { content: string; is_reminder: boolean }
 
 export type StepNotes = { profiles: Partial<{ [key in string]: ProfileStepNotes }> }
 
 export type StepNotesError = { Malformed: JsonError } | { CreateDir: string } | { ConfDir: string } | { SerializeStepNotes: JsonError } | { UnhandledIo: string } | { SaveStepNotes: string }
 
+/**
+ * Rewards of the quests completed at a step, `step` is a step index.
+ */
+export type StepRewards = { step: number; quests: QuestRewards[]; experience: number; kamas: number }
+
 export type Summary = { quests: QuestSummary[] }
 
 export type SummaryQuestStatus = { setup: number } | { started: number } | { inProgress: number } | { completed: number }
@@ -116,17 +398,68 @@ export type SyncProgressPayload = { id: number; current_step: number; steps: Par
 
 export type SyncResponse = { profiles: RemoteProfile[] }
 
-export type UpdateAllAtOnceResult = { type: "success" } | { type: "failure"; message: string } | { type: "offline" }
+export type TravelItinerary = { guideId: number; stops: TravelStop[];
+/**
+ * Travel commands of the stops, one per line.
+ */
+text: string }
+
+/**
+ * Consecutive steps on the same map, `from_step` and `to_step` are inclusive step indices.
+ */
+export type TravelStop = { fromStep: number; toStep: number; posX: number; posY: number; map: string | null; command: string }
+
+export type UpdateAllAtOnceResult = { type: "success" } | { type: "failure"; message: string } | { type: "offline" } |
+/**
+ * Not updated because of its update policy.
+ */
+{ type: "skipped"; policy: UpdatePolicy }
 
 export type UpdateError = { CheckUpdateError: string } | { GetUpdaterError: string }
 
+export type UpdatePolicy =
+/**
+ * Updated by the batch updater, at launch or when every guide is updated.
+ */
+"auto" |
+/**
+ * Available updates are reported by `guideUpdatesAvailable` and only installed once confirmed.
+ */
+"notifyOnly" |
+/**
+ * Never updated by the batch updater.
+ */
+"frozen"
+
 export type User = { id: number; name: string; is_admin: number; is_certified: number; lang: string }
 
 export type UserError = "TokensNotFound" | "NotConnected" | { FailedToGetUser: string } | { InvalidUserResponse: string }
 
 export type ViewedNotifications = { viewed_ids: number[] }
 
-const ARGS_MAP = { 'almanax':'{"get":["level","date"]}', 'api':'{"isAppVersionOld":[]}', 'base':'{"isProduction":[],"newId":[],"openUrl":["url"],"startup":[]}', 'conf':'{"get":[],"reset":[],"set":["conf"],"toggleGuideCheckbox":["guide_id","step_index","checkbox_index"]}', 'deep_link':'{"openGuideRequest":["guide_id","step"]}', 'dofusdb':'{"openHunt":["lang"],"openMap":["lang"]}', 'guides':'{"copyCurrentGuideStep":[],"deleteGuidesFromSystem":["guides_or_folders_to_delete"],"downloadGuideFromServer":["guide_id","folder"],"getFlatGuides":["folder"],"getGuideFromServer":["guide_id"],"getGuideSummary":["guide_id"],"getGuides":["folder"],"getGuidesFromServer":["status"],"getRecentGuides":["profile_id"],"guideExists":["guide_id"],"hasGuidesNotUpdated":[],"malformedGuidesRemoved":["files"],"openGuidesFolder":[],"registerGuideClose":["guide_id","profile_id"],"registerGuideOpen":["guide_id","profile_id"],"removeProfileFromRecentGuides":["profile_id"],"setRecentGuides":["profile_id","guide_ids"],"updateAllAtOnce":[]}', 'image':'{"fetchImage":["url"]}', 'image_viewer':'{"closeImageViewer":["window_label"],"openImageViewer":["image_url","title"]}', 'notifications':'{"getUnviewedNotifications":[],"getViewedNotifications":[],"markNotificationAsViewed":["notification_id"]}', 'oauth':'{"cleanAuthTokens":[],"getAuthTokens":[],"onJwtExpired":[],"onOAuthFlowEnd":[],"startOAuthFlow":[]}', 'pinnedGuides':'{"get":[],"pinGuide":["profile_id","guide_id"],"unpinGuide":["profile_id","guide_id"]}', 'report':'{"send_report":["payload"]}', 'security':'{"getWhiteList":[]}', 'shortcuts':'{"reregister":[]}', 'stepNotes':'{"get":[],"setStepNote":["profile_id","guide_id","step_index","note","is_reminder"]}', 'sync':'{"createProfile":["name","uuid"],"deleteProfile":["server_id"],"renameProfile":["server_id","name"],"syncProfiles":[],"syncProgress":["server_id","guide_id","current_step","steps"]}', 'update':'{"startUpdate":[]}', 'user':'{"getMe":[]}' }
+export type Zaap = { name: string; posX: number; posY: number }
+
+export type ZaapPlan = { guideId: number;
+/**
+ * Zaap to start the guide from.
+ */
+start: NearestZaap | null; legs: ZaapPlanLeg[]; walkingDistance: number; distance: number }
+
+/**
+ * Move between two consecutive stops of a guide, through the zaaps near them when it is shorter
+ * than walking.
+ */
+export type ZaapPlanLeg = { fromStep: number; toStep: number; walkingDistance: number;
+/**
+ * Zaap to walk to from the previous stop, `None` when walking is shorter.
+ */
+departureZaap: Zaap | null; arrivalZaap: Zaap | null;
+/**
+ * Maps walked following the plan.
+ */
+distance: number }
+
+const ARGS_MAP = { 'almanax':'{"get":["level","date"]}', 'api':'{"isAppVersionOld":[]}', 'base':'{"isProduction":[],"newId":[],"openUrl":["url"],"startup":[]}', 'conf':'{"get":[],"reset":[],"set":["conf"],"setQuestCompleted":["quest_name","completed"],"toggleGuideCheckbox":["guide_id","step_index","checkbox_index"]}', 'deep_link':'{"openGuideRequest":["guide_id","step"]}', 'dofusdb':'{"openHunt":["lang"],"openMap":["lang"]}', 'guides':'{"copyCurrentGuideStep":[],"createFolder":["folder"],"createLocalGuide":["folder","name","description","lang","game_type"],"deleteGuidesFromSystem":["guides_or_folders_to_delete"],"deleteLocalGuideStep":["guide_id","index"],"diffGuideVersions":["guide_id","from","to"],"downloadGuideFromServer":["guide_id","folder"],"downloadLinkedGuides":["guide_id","folder","recursive"],"exportBundle":["path","guide_ids","folders","include_profile"],"getCatalog":["status"],"getFlatGuides":["folder"],"getGuide":["guide_id"],"getGuideDependencies":["guide_id"],"getGuideFromServer":["guide_id"],"getGuideHeaders":["folder"],"getGuideRewards":["guide_id"],"getGuideShoppingList":["guide_id","from_step","to_step"],"getGuideSummary":["guide_id"],"getGuideVersions":["guide_id"],"getGuides":["folder"],"getGuidesFromServer":["status"],"getLastChangelog":[],"getLastSession":["profile_id"],"getQuarantinedGuides":[],"getQuestCompletions":["quest_name"],"getQuestIndex":[],"getRecentGuides":["profile_id"],"getSkippableSteps":["guide_id"],"getUpdatePolicies":[],"guideExists":["guide_id"],"guideStepsRemapped":["guides"],"guideUpdatesAvailable":["guides"],"guidesChanged":[],"guidesDownloadProgress":["progress"],"guidesUpdated":["changelog"],"hasGuidesNotUpdated":[],"importBundle":["path","target_folder","conflict","include_profile"],"insertLocalGuideStep":["guide_id","index","step"],"lint":["guide_id","check_images"],"makeGuideAvailableOffline":["guide_id"],"malformedGuidesRecovered":["report"],"malformedGuidesRemoved":["files"],"markCatalogVisited":[],"moveFolder":["folder","destination"],"moveGuide":["guide_id","destination"],"moveLocalGuideStep":["guide_id","from","to"],"openGuidesFolder":[],"purgeQuarantinedGuides":["entries"],"queryGuides":["query"],"registerGuideClose":["guide_id","profile_id"],"registerGuideOpen":["guide_id","profile_id"],"removeProfileFromRecentGuides":["profile_id"],"renameFolder":["folder","name"],"restoreQuarantinedGuide":["entry"],"rollbackGuide":["guide_id","version"],"search":["query"],"setRecentGuides":["profile_id","guide_ids"],"setUpdatePolicy":["guide_id","policy"],"updateAllAtOnce":[],"updateGuides":["guide_ids"],"updateLocalGuide":["guide_id","name","description","lang","game_type"],"updateLocalGuideStep":["guide_id","index","step"]}', 'image':'{"fetchImage":["url"]}', 'image_viewer':'{"closeImageViewer":["window_label"],"openImageViewer":["image_url","title"]}', 'notifications':'{"getUnviewedNotifications":[],"getViewedNotifications":[],"markNotificationAsViewed":["notification_id"]}', 'oauth':'{"cleanAuthTokens":[],"getAuthTokens":[],"onJwtExpired":[],"onOAuthFlowEnd":[],"startOAuthFlow":[]}', 'pinnedGuides':'{"get":[],"pinGuide":["profile_id","guide_id"],"unpinGuide":["profile_id","guide_id"]}', 'report':'{"send_report":["payload"]}', 'security':'{"getWhiteList":[]}', 'shortcuts':'{"reregister":[]}', 'stepNotes':'{"get":[],"setStepNote":["profile_id","guide_id","step_index","note","is_reminder"]}', 'sync':'{"createProfile":["name","uuid"],"deleteProfile":["server_id"],"renameProfile":["server_id","name"],"syncProfiles":[],"syncProgress":["server_id","guide_id","current_step","steps"]}', 'travel':'{"getGuideNearestZaaps":["guide_id"],"getItinerary":["guide_id","from_step","to_step"],"getZaapPlan":["guide_id"],"nearestZaap":["pos_x","pos_y"],"reloadZaaps":[]}', 'update':'{"startUpdate":[]}', 'user':'{"getMe":[]}' }
 export type Router = { "almanax": {get: (level: number, date: string) => Promise<AlmanaxReward>},
 "api": {isAppVersionOld: () => Promise<IsOld>},
 "base": {isProduction: () => Promise<boolean>,
@@ -136,28 +469,73 @@ startup: () => Promise<void>},
 "conf": {get: () => Promise<Conf>,
 reset: () => Promise<null>,
 set: (conf: Conf) => Promise<null>,
+setQuestCompleted: (questName: string, completed: boolean) => Promise<string[]>,
 toggleGuideCheckbox: (guideId: number, stepIndex: number, checkboxIndex: number) => Promise<number>},
 "deep_link": {openGuideRequest: (guideId: number, step: OpenGuideStep) => Promise<void>},
 "dofusdb": {openHunt: (lang: string) => Promise<null>,
 openMap: (lang: string) => Promise<null>},
 "guides": {copyCurrentGuideStep: () => Promise<void>,
+createFolder: (folder: string) => Promise<null>,
+createLocalGuide: (folder: string, name: string, description: string | null, lang: GuideLang, gameType: GameType) => Promise<GuideWithSteps>,
 deleteGuidesFromSystem: (guidesOrFoldersToDelete: GuideOrFolderToDelete[]) => Promise<null>,
+deleteLocalGuideStep: (guideId: number, index: number) => Promise<GuideWithSteps>,
+diffGuideVersions: (guideId: number, from: string | null, to: string | null) => Promise<StepChange[]>,
 downloadGuideFromServer: (guideId: number, folder: string) => Promise<Guides>,
+downloadLinkedGuides: (guideId: number | null, folder: string, recursive: boolean) => Promise<LinkedGuidesDownload>,
+exportBundle: (path: string, guideIds: number[], folders: string[], includeProfile: boolean) => Promise<BundleExport>,
+getCatalog: (status: Status | null) => Promise<GuideCatalog>,
 getFlatGuides: (folder: string) => Promise<GuideWithSteps[]>,
+getGuide: (guideId: number) => Promise<GuideWithSteps>,
+getGuideDependencies: (guideId: number | null) => Promise<GuideDependencies>,
 getGuideFromServer: (guideId: number) => Promise<GuideWithSteps>,
+getGuideHeaders: (folder: string | null) => Promise<GuideHeaderOrFolder[]>,
+getGuideRewards: (guideId: number) => Promise<GuideRewards>,
+getGuideShoppingList: (guideId: number, fromStep: number | null, toStep: number | null) => Promise<ShoppingListEntry[]>,
 getGuideSummary: (guideId: number) => Promise<Summary>,
+getGuideVersions: (guideId: number) => Promise<GuideVersion[]>,
 getGuides: (folder: string | null) => Promise<GuidesOrFolder[]>,
 getGuidesFromServer: (status: Status | null) => Promise<Guide[]>,
+getLastChangelog: () => Promise<GuidesChangelog | null>,
+getLastSession: (profileId: string) => Promise<GuideSession | null>,
+getQuarantinedGuides: () => Promise<QuarantinedGuideFile[]>,
+getQuestCompletions: (questName: string) => Promise<QuestCompletion[]>,
+getQuestIndex: () => Promise<IndexedQuest[]>,
 getRecentGuides: (profileId: string) => Promise<number[]>,
+getSkippableSteps: (guideId: number) => Promise<number[]>,
+getUpdatePolicies: () => Promise<GuideUpdatePolicies>,
 guideExists: (guideId: number) => Promise<boolean>,
+guideStepsRemapped: (guides: RemappedGuideSteps[]) => Promise<void>,
+guideUpdatesAvailable: (guides: AvailableGuideUpdate[]) => Promise<void>,
+guidesChanged: () => Promise<void>,
+guidesDownloadProgress: (progress: GuidesDownloadProgress) => Promise<void>,
+guidesUpdated: (changelog: GuidesChangelog) => Promise<void>,
 hasGuidesNotUpdated: () => Promise<boolean>,
+importBundle: (path: string, targetFolder: string, conflict: BundleConflictStrategy, includeProfile: boolean) => Promise<BundleImport>,
+insertLocalGuideStep: (guideId: number, index: number | null, step: GuideStep) => Promise<GuideWithSteps>,
+lint: (guideId: number, checkImages: boolean) => Promise<GuideLintReport>,
+makeGuideAvailableOffline: (guideId: number) => Promise<OfflineGuideImages>,
+malformedGuidesRecovered: (report: MalformedGuidesReport) => Promise<void>,
 malformedGuidesRemoved: (files: RemovedGuideFile[]) => Promise<void>,
+markCatalogVisited: () => Promise<null>,
+moveFolder: (folder: string, destination: string) => Promise<null>,
+moveGuide: (guideId: number, destination: string) => Promise<GuideWithSteps>,
+moveLocalGuideStep: (guideId: number, from: number, to: number) => Promise<GuideWithSteps>,
 openGuidesFolder: () => Promise<null>,
+purgeQuarantinedGuides: (entries: string[] | null) => Promise<null>,
+queryGuides: (query: GuideQuery) => Promise<GuidesPage>,
 registerGuideClose: (guideId: number, profileId: string) => Promise<null>,
 registerGuideOpen: (guideId: number, profileId: string) => Promise<null>,
 removeProfileFromRecentGuides: (profileId: string) => Promise<null>,
+renameFolder: (folder: string, name: string) => Promise<null>,
+restoreQuarantinedGuide: (entry: string) => Promise<GuideWithSteps>,
+rollbackGuide: (guideId: number, version: string) => Promise<GuideRollback>,
+search: (query: string) => Promise<GuideSearchResult[]>,
 setRecentGuides: (profileId: string, guideIds: number[]) => Promise<null>,
-updateAllAtOnce: () => Promise<Partial<{ [key in number]: UpdateAllAtOnceResult }>>},
+setUpdatePolicy: (guideId: number, policy: UpdatePolicy) => Promise<GuideUpdatePolicies>,
+updateAllAtOnce: () => Promise<Partial<{ [key in number]: UpdateAllAtOnceResult }>>,
+updateGuides: (guideIds: number[]) => Promise<Partial<{ [key in number]: UpdateAllAtOnceResult }>>,
+updateLocalGuide: (guideId: number, name: string, description: string | null, lang: GuideLang, gameType: GameType) => Promise<GuideWithSteps>,
+updateLocalGuideStep: (guideId: number, index: number, step: GuideStep) => Promise<GuideWithSteps>},
 "image": {fetchImage: (url: string) => Promise<number[]>},
 "image_viewer": {closeImageViewer: (windowLabel: string) => Promise<null>,
 openImageViewer: (imageUrl: string, title: string | null) => Promise<string>},
@@ -182,6 +560,11 @@ deleteProfile: (serverId: number) => Promise<null>,
 renameProfile: (serverId: number, name: string) => Promise<null>,
 syncProfiles: () => Promise<SyncResponse>,
 syncProgress: (serverId: number, guideId: number, currentStep: number, steps: Partial<{ [key in number]: ConfStep }>) => Promise<null>},
+"travel": {getGuideNearestZaaps: (guideId: number) => Promise<StepNearestZaap[]>,
+getItinerary: (guideId: number, fromStep: number | null, toStep: number | null) => Promise<TravelItinerary>,
+getZaapPlan: (guideId: number) => Promise<ZaapPlan>,
+nearestZaap: (posX: number, posY: number) => Promise<NearestZaap | null>,
+reloadZaaps: () => Promise<Zaap[]>},
 "update": {startUpdate: () => Promise<null>},
 "user": {getMe: () => Promise<User>} };
 
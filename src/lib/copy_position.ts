@@ -1,11 +1,33 @@
 import { writeText } from '@tauri-apps/plugin-clipboard-manager'
 
-export async function copyPosition(posX: number, posY: number, autoTravelCopy: boolean): Promise<void> {
+const DEFAULT_TRAVEL_COMMAND_TEMPLATE = '/travel {x},{y}'
+
+/**
+ * Same format as the travel commands built by the backend, `{map}` is left empty as positions
+ * found in the text of a step have no map.
+ */
+export function travelCommand(template: string, posX: number, posY: number): string {
+  const command = template.trim() === '' ? DEFAULT_TRAVEL_COMMAND_TEMPLATE : template
+
+  return command.replaceAll('{x}', `${posX}`).replaceAll('{y}', `${posY}`).replaceAll('{map}', '')
+}
+
+/**
+ * Copy a position, as a travel command when `autoTravelCopy` is on, and return the copied text.
+ */
+export async function copyPosition(
+  posX: number,
+  posY: number,
+  autoTravelCopy: boolean,
+  travelCommandTemplate: string,
+): Promise<string> {
   let copy = `[${posX},${posY}]`
 
   if (autoTravelCopy) {
-    copy = `/travel ${posX},${posY}`
+    copy = travelCommand(travelCommandTemplate, posX, posY)
   }
 
   await writeText(copy)
+
+  return copy
 }
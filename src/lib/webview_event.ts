@@ -8,7 +8,8 @@ export type AvailableEvent =
   | 'update-in-progress'
 
 export type PayloadByEvent = {
-  'go-to-next-guide-step': null
+  /** Number of steps to move forward, more than 1 when completed quest steps are skipped. */
+  'go-to-next-guide-step': number | null
   'go-to-previous-guide-step': null
   'update-started': null
   'update-finished': null
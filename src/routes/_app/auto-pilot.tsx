@@ -56,7 +56,7 @@ function AutoPilotPage() {
                   <Button
                     onClick={async () => {
                       const [x, y] = autoPilot.position.split(',').map((n) => Number.parseInt(n, 10))
-                      await copyPosition(x, y, conf.data.autoTravelCopy)
+                      await copyPosition(x, y, conf.data.autoTravelCopy, conf.data.travelCommandTemplate)
                     }}
                     size="icon"
                     title={conf.data.autoTravelCopy ? t`Copier la commande autopilote` : t`Copier la position`}